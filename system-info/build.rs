@@ -0,0 +1,3 @@
+fn main() {
+    slint_build::compile("ui/app-windows.slint").expect("failed to compile the Slint UI");
+}
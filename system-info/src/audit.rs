@@ -0,0 +1,84 @@
+use std::fs::{self, OpenOptions};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+
+use chrono::Local;
+
+use crate::process_actions::{ActionError, ProcessAction};
+
+/// Number of entries kept in memory for display.
+const RECENT_ENTRIES: usize = 50;
+
+/// Append-only record of every action taken against a process.
+///
+/// Entries go to `system-info/audit.log` in the user's state directory as well as to the
+/// application log, and the most recent ones are kept for the UI.
+pub struct AuditLog {
+    path: Option<PathBuf>,
+    recent: Vec<String>,
+}
+
+impl AuditLog {
+    pub fn new() -> Self {
+        Self {
+            path: default_path(),
+            recent: Vec::new(),
+        }
+    }
+
+    /// Records the outcome of an action against a process.
+    pub fn record(
+        &mut self,
+        action: ProcessAction,
+        pid: u32,
+        name: &str,
+        outcome: &Result<(), ActionError>,
+    ) {
+        let result = match outcome {
+            Ok(()) => "ok".to_string(),
+            Err(err) => format!("failed: {}", err),
+        };
+        let entry = format!(
+            "{} {} pid={} name={:?} {}",
+            Local::now().format("%Y-%m-%d %H:%M:%S"),
+            action,
+            pid,
+            name,
+            result
+        );
+        log::info!("Process action: {}", entry);
+
+        if let Some(path) = &self.path {
+            if let Err(err) = append_line(path, &entry) {
+                log::warn!("Failed to write audit log {}: {}", path.display(), err);
+            }
+        }
+
+        if self.recent.len() == RECENT_ENTRIES {
+            self.recent.remove(0);
+        }
+        self.recent.push(entry);
+    }
+
+    /// Most recent entries, newest first.
+    pub fn recent(&self) -> impl Iterator<Item = &String> {
+        self.recent.iter().rev()
+    }
+}
+
+fn append_line(path: &Path, line: &str) -> io::Result<()> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    let mut file = OpenOptions::new().create(true).append(true).open(path)?;
+    writeln!(file, "{}", line)
+}
+
+/// `$XDG_STATE_HOME`, `~/.local/state` or `%LOCALAPPDATA%`, whichever applies first.
+fn default_path() -> Option<PathBuf> {
+    let state_dir = std::env::var_os("XDG_STATE_HOME")
+        .map(PathBuf::from)
+        .or_else(|| std::env::var_os("HOME").map(|home| PathBuf::from(home).join(".local/state")))
+        .or_else(|| std::env::var_os("LOCALAPPDATA").map(PathBuf::from))?;
+    Some(state_dir.join("system-info").join("audit.log"))
+}
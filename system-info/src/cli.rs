@@ -0,0 +1,92 @@
+use std::net::SocketAddr;
+use std::path::PathBuf;
+use std::time::Duration;
+
+use clap::Parser;
+
+use crate::csv_log::Rotation;
+use crate::export::Format;
+use crate::recorder::Retention;
+
+/// Command-line options. Without any, the graphical monitor opens.
+#[derive(Debug, Parser)]
+#[command(
+    version,
+    about = "Shows CPU, memory, GPU, disk, network and process information"
+)]
+pub struct Cli {
+    /// Print a single snapshot to stdout and exit without opening a window.
+    #[arg(long, visible_alias = "headless")]
+    pub once: bool,
+
+    /// Print a single snapshot in a machine-readable format instead of a table. Implies --once.
+    #[arg(long, value_enum)]
+    pub format: Option<Format>,
+
+    /// Serve Prometheus/OpenMetrics metrics at http://ADDR/metrics, plus the JSON API
+    /// under /api, instead of opening a window.
+    #[arg(long, value_name = "ADDR")]
+    pub serve_metrics: Option<SocketAddr>,
+
+    /// Serve a browser dashboard at http://ADDR/, along with the JSON API and /metrics,
+    /// instead of opening a window.
+    #[arg(long, value_name = "ADDR", conflicts_with_all = ["serve_metrics", "once", "format"])]
+    pub web: Option<SocketAddr>,
+
+    /// Show the monitor in the terminal instead of opening a window, e.g. over SSH.
+    #[arg(long, conflicts_with_all = ["serve_metrics", "web", "once", "format"])]
+    pub tui: bool,
+
+    /// Serve the JSON API and /metrics at ADDR while the window is open.
+    #[arg(long, value_name = "ADDR", conflicts_with_all = ["serve_metrics", "web", "tui", "once", "format"])]
+    pub api: Option<SocketAddr>,
+
+    /// Record every collected snapshot to the SQLite database at PATH, alongside the
+    /// window, --serve-metrics, --web or --log-csv.
+    #[arg(long, value_name = "PATH", conflicts_with_all = ["once", "format", "tui"])]
+    pub record: Option<PathBuf>,
+
+    /// Open the window on a recording made with --record instead of live data.
+    #[arg(long, value_name = "FILE", conflicts_with_all = ["serve_metrics", "web", "tui", "api", "record", "once", "format"])]
+    pub replay: Option<PathBuf>,
+
+    /// Append one row per --interval to the CSV file at PATH instead of opening a window.
+    #[arg(long, value_name = "PATH", conflicts_with_all = ["serve_metrics", "web", "tui", "api", "replay", "once", "format"])]
+    pub log_csv: Option<PathBuf>,
+
+    /// How often --log-csv writes a row, e.g. 5s or 1m.
+    #[arg(long, value_name = "DURATION", default_value = "5s", value_parser = humantime::parse_duration, requires = "log_csv")]
+    pub interval: Duration,
+
+    /// Start a new --log-csv file each day or once it reaches a size, e.g. daily or 100MB.
+    /// The finished file is renamed with the day or time it was rotated.
+    #[arg(long, value_name = "WHEN", requires = "log_csv")]
+    pub rotate: Option<Rotation>,
+
+    /// Push snapshots to the InfluxDB, Graphite and StatsD sinks listed in the TOML FILE,
+    /// alongside the window, --serve-metrics, --web or --log-csv.
+    #[arg(long, value_name = "FILE", conflicts_with_all = ["once", "format", "tui", "replay"])]
+    pub sinks: Option<PathBuf>,
+
+    /// How long to keep per-tick samples in the recording, e.g. 12h or 7d.
+    #[arg(long, value_name = "DURATION", default_value = "24h", value_parser = humantime::parse_duration)]
+    pub retain_raw: Duration,
+
+    /// How long to keep one-minute aggregates in the recording.
+    #[arg(long, value_name = "DURATION", default_value = "30d", value_parser = humantime::parse_duration)]
+    pub retain_minutes: Duration,
+
+    /// How long to keep one-hour aggregates in the recording.
+    #[arg(long, value_name = "DURATION", default_value = "365d", value_parser = humantime::parse_duration)]
+    pub retain_hours: Duration,
+}
+
+impl Cli {
+    pub fn retention(&self) -> Retention {
+        Retention {
+            raw: self.retain_raw,
+            minute: self.retain_minutes,
+            hour: self.retain_hours,
+        }
+    }
+}
@@ -0,0 +1,253 @@
+use std::time::{Duration, Instant};
+
+use crate::snapshot::{
+    CpuSnapshot, GpuSnapshot, InterfaceSnapshot, MemorySnapshot, ProcessSnapshot, SensorReading,
+    StorageSnapshot,
+};
+
+mod cpu;
+mod disk;
+mod diskstats;
+#[cfg(test)]
+mod fixture;
+mod gpu;
+mod hwmon;
+mod ifaddrs;
+mod meminfo;
+mod memory;
+mod netdev;
+mod network;
+mod nvml;
+mod process;
+mod sensors;
+mod vram;
+
+pub use cpu::CpuCollector;
+pub use disk::DiskCollector;
+pub use gpu::GpuCollector;
+pub use memory::MemoryCollector;
+pub use network::NetworkCollector;
+pub use process::ProcessCollector;
+pub use sensors::SensorCollector;
+
+/// A typed reading produced by a single collector.
+#[derive(Debug, Clone)]
+pub enum Sample {
+    Cpu(CpuSnapshot),
+    Memory(MemorySnapshot),
+    Gpu(Vec<GpuSnapshot>),
+    Processes(Vec<ProcessSnapshot>),
+    Storage(StorageSnapshot),
+    Network(Vec<InterfaceSnapshot>),
+    Sensors(Vec<SensorReading>),
+}
+
+/// A source of metrics that can be scheduled independently of the others.
+pub trait Collector: Send {
+    /// Stable identifier used to enable or disable the collector.
+    fn name(&self) -> &'static str;
+
+    /// How often the collector wants to be sampled.
+    fn interval(&self) -> Duration;
+
+    /// Takes a fresh reading.
+    fn collect(&mut self) -> Sample;
+}
+
+struct Entry {
+    collector: Box<dyn Collector>,
+    enabled: bool,
+    last_run: Option<Instant>,
+}
+
+impl Entry {
+    fn is_due(&self, now: Instant) -> bool {
+        match self.last_run {
+            Some(last_run) => now.duration_since(last_run) >= self.collector.interval(),
+            None => true,
+        }
+    }
+}
+
+/// Holds every known collector and decides which ones are due on each tick.
+#[derive(Default)]
+pub struct CollectorRegistry {
+    entries: Vec<Entry>,
+}
+
+impl CollectorRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Registry with the built-in CPU, memory, GPU, process, disk, network and sensor collectors enabled.
+    pub fn with_defaults() -> Self {
+        let mut registry = Self::new();
+        registry.register(Box::new(CpuCollector::new()));
+        registry.register(Box::new(MemoryCollector::new()));
+        registry.register(Box::new(GpuCollector::new()));
+        registry.register(Box::new(ProcessCollector::new()));
+        registry.register(Box::new(DiskCollector::new()));
+        registry.register(Box::new(NetworkCollector::new()));
+        registry.register(Box::new(SensorCollector::new()));
+        registry
+    }
+
+    /// Adds a collector; it is enabled and runs on the next poll.
+    pub fn register(&mut self, collector: Box<dyn Collector>) {
+        self.entries.push(Entry {
+            collector,
+            enabled: true,
+            last_run: None,
+        });
+    }
+
+    /// Enables or disables a collector by name. Returns `false` if no such collector exists.
+    pub fn set_enabled(&mut self, name: &str, enabled: bool) -> bool {
+        match self
+            .entries
+            .iter_mut()
+            .find(|entry| entry.collector.name() == name)
+        {
+            Some(entry) => {
+                entry.enabled = enabled;
+                // Re-enabled collectors should report straight away rather than after a full interval
+                entry.last_run = None;
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Runs every enabled collector that is due and returns their samples.
+    pub fn poll(&mut self, now: Instant) -> Vec<Sample> {
+        self.run(|entry| entry.is_due(now), now)
+    }
+
+    /// Runs every enabled collector regardless of its schedule.
+    pub fn collect_all(&mut self) -> Vec<Sample> {
+        self.run(|_| true, Instant::now())
+    }
+
+    /// Time until the next enabled collector becomes due.
+    pub fn next_due(&self, now: Instant) -> Duration {
+        self.entries
+            .iter()
+            .filter(|entry| entry.enabled)
+            .map(|entry| match entry.last_run {
+                Some(last_run) => {
+                    (last_run + entry.collector.interval()).saturating_duration_since(now)
+                }
+                None => Duration::ZERO,
+            })
+            .min()
+            .unwrap_or(Duration::from_secs(1))
+    }
+
+    fn run(&mut self, should_run: impl Fn(&Entry) -> bool, now: Instant) -> Vec<Sample> {
+        let mut samples = Vec::new();
+
+        for entry in self.entries.iter_mut().filter(|entry| entry.enabled) {
+            if !should_run(entry) {
+                continue;
+            }
+
+            samples.push(entry.collector.collect());
+            log::trace!("Collector '{}' produced a sample", entry.collector.name());
+            entry.last_run = Some(now);
+        }
+
+        samples
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Reports its own name as the CPU brand, so tests can see which collectors ran.
+    struct Stub {
+        name: &'static str,
+        interval: Duration,
+    }
+
+    impl Collector for Stub {
+        fn name(&self) -> &'static str {
+            self.name
+        }
+
+        fn interval(&self) -> Duration {
+            self.interval
+        }
+
+        fn collect(&mut self) -> Sample {
+            Sample::Cpu(CpuSnapshot {
+                brand: self.name.to_string(),
+                ..Default::default()
+            })
+        }
+    }
+
+    fn registry() -> CollectorRegistry {
+        let mut registry = CollectorRegistry::new();
+        for (name, secs) in [("fast", 1), ("slow", 3)] {
+            registry.register(Box::new(Stub {
+                name,
+                interval: Duration::from_secs(secs),
+            }));
+        }
+        registry
+    }
+
+    fn ran(samples: Vec<Sample>) -> Vec<String> {
+        samples
+            .into_iter()
+            .map(|sample| match sample {
+                Sample::Cpu(cpu) => cpu.brand,
+                _ => unreachable!(),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn each_collector_runs_on_its_own_interval() {
+        let mut registry = registry();
+        let start = Instant::now();
+        let at = |secs| start + Duration::from_secs(secs);
+
+        assert_eq!(ran(registry.poll(start)), ["fast", "slow"]);
+        assert_eq!(registry.next_due(start), Duration::from_secs(1));
+        assert_eq!(ran(registry.poll(at(1))), ["fast"]);
+        assert_eq!(ran(registry.poll(at(2))), ["fast"]);
+        assert_eq!(ran(registry.poll(at(3))), ["fast", "slow"]);
+
+        // Nothing is due half-way through the fast interval
+        let halfway = at(3) + Duration::from_millis(500);
+        assert!(registry.poll(halfway).is_empty());
+        assert_eq!(registry.next_due(halfway), Duration::from_millis(500));
+    }
+
+    #[test]
+    fn disabled_collectors_are_skipped_until_re_enabled() {
+        let mut registry = registry();
+        let start = Instant::now();
+
+        assert!(registry.set_enabled("fast", false));
+        assert!(!registry.set_enabled("missing", false));
+        assert_eq!(ran(registry.poll(start)), ["slow"]);
+        // Only the slow collector is left to wait for
+        assert_eq!(registry.next_due(start), Duration::from_secs(3));
+        assert_eq!(ran(registry.collect_all()), ["slow"]);
+
+        let later = start + Duration::from_secs(1);
+        assert!(registry.set_enabled("fast", true));
+        assert_eq!(registry.next_due(later), Duration::ZERO);
+        assert_eq!(ran(registry.poll(later)), ["fast"]);
+    }
+
+    #[test]
+    fn an_empty_registry_checks_back_after_a_second() {
+        let registry = CollectorRegistry::new();
+        assert_eq!(registry.next_due(Instant::now()), Duration::from_secs(1));
+    }
+}
@@ -0,0 +1,63 @@
+use std::time::Duration;
+
+use sysinfo::{CpuExt, System, SystemExt};
+
+use super::{Collector, Sample};
+use crate::snapshot::{CoreSnapshot, CpuSnapshot};
+
+/// Samples usage and frequency of every logical core.
+pub struct CpuCollector {
+    system: System,
+}
+
+impl CpuCollector {
+    pub fn new() -> Self {
+        let mut system = System::new();
+        // sysinfo derives usage from the ticks between two refreshes; take the first now
+        system.refresh_cpu();
+        Self { system }
+    }
+}
+
+impl Collector for CpuCollector {
+    fn name(&self) -> &'static str {
+        "cpu"
+    }
+
+    fn interval(&self) -> Duration {
+        Duration::from_secs(1)
+    }
+
+    fn collect(&mut self) -> Sample {
+        self.system.refresh_cpu();
+
+        let cpus = self.system.cpus();
+        let usage_percent = if cpus.is_empty() {
+            0.0
+        } else {
+            cpus.iter().map(|cpu| cpu.cpu_usage()).sum::<f32>() / cpus.len() as f32
+        };
+
+        let cores = cpus
+            .iter()
+            .map(|cpu| CoreSnapshot {
+                name: cpu.name().to_string(),
+                usage_percent: cpu.cpu_usage(),
+                frequency_mhz: cpu.frequency(),
+            })
+            .collect();
+
+        // Every logical core reports the same package identity
+        let (brand, vendor_id) = cpus
+            .first()
+            .map(|cpu| (cpu.brand().trim().to_string(), cpu.vendor_id().to_string()))
+            .unwrap_or_default();
+
+        Sample::Cpu(CpuSnapshot {
+            brand,
+            vendor_id,
+            usage_percent,
+            cores,
+        })
+    }
+}
@@ -0,0 +1,59 @@
+use std::time::Duration;
+
+use sysinfo::{DiskExt, System, SystemExt};
+
+use super::diskstats::DiskStatsReader;
+use super::{Collector, Sample};
+use crate::snapshot::{MountSnapshot, StorageSnapshot};
+
+/// Samples mounted filesystems and block device throughput.
+pub struct DiskCollector {
+    system: System,
+    diskstats: DiskStatsReader,
+}
+
+impl DiskCollector {
+    pub fn new() -> Self {
+        let mut diskstats = DiskStatsReader::default();
+        // The reader turns /proc/diskstats totals into rates against its previous read
+        diskstats.read();
+        Self {
+            system: System::new(),
+            diskstats,
+        }
+    }
+}
+
+impl Collector for DiskCollector {
+    fn name(&self) -> &'static str {
+        "disks"
+    }
+
+    fn interval(&self) -> Duration {
+        Duration::from_secs(2)
+    }
+
+    fn collect(&mut self) -> Sample {
+        // Re-list rather than refresh so newly plugged or unmounted media show up
+        self.system.refresh_disks_list();
+
+        let mounts = self
+            .system
+            .disks()
+            .iter()
+            .map(|disk| MountSnapshot {
+                device: disk.name().to_string_lossy().into_owned(),
+                mount_point: disk.mount_point().display().to_string(),
+                file_system: String::from_utf8_lossy(disk.file_system()).into_owned(),
+                total_bytes: disk.total_space(),
+                available_bytes: disk.available_space(),
+                is_removable: disk.is_removable(),
+            })
+            .collect();
+
+        Sample::Storage(StorageSnapshot {
+            mounts,
+            devices: self.diskstats.read(),
+        })
+    }
+}
@@ -0,0 +1,195 @@
+use std::collections::HashMap;
+use std::fs;
+use std::path::PathBuf;
+use std::time::Instant;
+
+use crate::snapshot::BlockDeviceSnapshot;
+
+/// `/proc/diskstats` always counts in 512-byte sectors, whatever the device's real sector size.
+const SECTOR_BYTES: u64 = 512;
+
+/// Cumulative counters of one device as the kernel reports them.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+struct DiskCounters {
+    reads: u64,
+    sectors_read: u64,
+    writes: u64,
+    sectors_written: u64,
+}
+
+impl DiskCounters {
+    /// Growth since an earlier reading. Counters only go backwards when they wrap
+    /// or the device is re-created, which is reported as no activity.
+    fn since(&self, earlier: &DiskCounters) -> DiskCounters {
+        DiskCounters {
+            reads: self.reads.saturating_sub(earlier.reads),
+            sectors_read: self.sectors_read.saturating_sub(earlier.sectors_read),
+            writes: self.writes.saturating_sub(earlier.writes),
+            sectors_written: self.sectors_written.saturating_sub(earlier.sectors_written),
+        }
+    }
+}
+
+/// Turns the cumulative counters in `/proc/diskstats` into per-second rates.
+///
+/// Only whole block devices are reported; partitions are skipped by checking
+/// for an entry under `/sys/block` (or whichever directory stands in for it).
+pub struct DiskStatsReader {
+    diskstats_path: PathBuf,
+    sys_block_root: PathBuf,
+    previous: HashMap<String, DiskCounters>,
+    last_read: Option<Instant>,
+}
+
+impl Default for DiskStatsReader {
+    fn default() -> Self {
+        Self::new("/proc/diskstats", "/sys/block")
+    }
+}
+
+impl DiskStatsReader {
+    pub fn new(diskstats_path: impl Into<PathBuf>, sys_block_root: impl Into<PathBuf>) -> Self {
+        Self {
+            diskstats_path: diskstats_path.into(),
+            sys_block_root: sys_block_root.into(),
+            previous: HashMap::new(),
+            last_read: None,
+        }
+    }
+
+    /// Rates since the previous call. The first call only records a baseline and reports zeros.
+    pub fn read(&mut self) -> Vec<BlockDeviceSnapshot> {
+        let Ok(text) = fs::read_to_string(&self.diskstats_path) else {
+            return Vec::new(); // Not Linux, or /proc is not mounted
+        };
+
+        let now = Instant::now();
+        let elapsed = self
+            .last_read
+            .map(|last_read| now.duration_since(last_read).as_secs_f64())
+            .filter(|elapsed| *elapsed > 0.0);
+        self.last_read = Some(now);
+
+        let mut current = HashMap::new();
+        let mut devices = Vec::new();
+        for (name, counters) in text.lines().filter_map(parse_line) {
+            if !self.is_whole_device(&name) {
+                continue;
+            }
+
+            let previous = self.previous.get(&name).copied();
+            current.insert(name.clone(), counters);
+            // Devices that never saw any I/O, such as unused loop and ram devices, are noise
+            if counters.reads == 0 && counters.writes == 0 {
+                continue;
+            }
+
+            let (delta, elapsed) = match (previous, elapsed) {
+                (Some(previous), Some(elapsed)) => (counters.since(&previous), elapsed),
+                _ => (DiskCounters::default(), 1.0),
+            };
+            let per_second = |count: u64| count as f64 / elapsed;
+
+            devices.push(BlockDeviceSnapshot {
+                name,
+                read_bytes_per_sec: per_second(delta.sectors_read * SECTOR_BYTES) as u64,
+                write_bytes_per_sec: per_second(delta.sectors_written * SECTOR_BYTES) as u64,
+                read_iops: per_second(delta.reads) as f32,
+                write_iops: per_second(delta.writes) as f32,
+                total_read_bytes: counters.sectors_read * SECTOR_BYTES,
+                total_written_bytes: counters.sectors_written * SECTOR_BYTES,
+                total_reads: counters.reads,
+                total_writes: counters.writes,
+            });
+        }
+
+        self.previous = current;
+        devices.sort_by(|a, b| a.name.cmp(&b.name));
+        devices
+    }
+
+    fn is_whole_device(&self, name: &str) -> bool {
+        // sysfs spells the `/` in names such as `cciss/c0d0` as `!`
+        self.sys_block_root.join(name.replace('/', "!")).exists()
+    }
+}
+
+/// Parses `major minor name reads merged sectors ms writes merged sectors ...`.
+fn parse_line(line: &str) -> Option<(String, DiskCounters)> {
+    let fields: Vec<&str> = line.split_whitespace().collect();
+    let number = |index: usize| fields.get(index)?.parse::<u64>().ok();
+
+    Some((
+        fields.get(2)?.to_string(),
+        DiskCounters {
+            reads: number(3)?,
+            sectors_read: number(5)?,
+            writes: number(7)?,
+            sectors_written: number(9)?,
+        },
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use super::*;
+
+    fn write_diskstats(path: &Path, nvme_reads: u64, nvme_sectors_read: u64) {
+        let text = format!(
+            "   7       0 loop0 0 0 0 0 0 0 0 0 0 0 0 0 0 0 0 0 0\n \
+             259       0 nvme0n1 {} 0 {} 100 2000 0 80000 300 0 400 500 0 0 0 0 0 0\n \
+             259       1 nvme0n1p1 900 0 1800 50 1000 0 40000 150 0 200 250 0 0 0 0 0 0\n",
+            nvme_reads, nvme_sectors_read
+        );
+        fs::write(path, text).unwrap();
+    }
+
+    #[test]
+    fn reports_whole_devices_that_saw_io() {
+        let dir = tempfile::tempdir().unwrap();
+        let diskstats = dir.path().join("diskstats");
+        let sys_block = dir.path().join("block");
+        for device in ["loop0", "nvme0n1"] {
+            fs::create_dir_all(sys_block.join(device)).unwrap();
+        }
+        write_diskstats(&diskstats, 1000, 2000);
+
+        let mut reader = DiskStatsReader::new(&diskstats, &sys_block);
+        let devices = reader.read();
+        // loop0 never did I/O and nvme0n1p1 is a partition
+        assert_eq!(devices.len(), 1);
+        let nvme = &devices[0];
+        assert_eq!(nvme.name, "nvme0n1");
+        assert_eq!((nvme.total_reads, nvme.total_writes), (1000, 2000));
+        assert_eq!(nvme.total_read_bytes, 2000 * SECTOR_BYTES);
+        assert_eq!(nvme.total_written_bytes, 80000 * SECTOR_BYTES);
+        // The first read is only a baseline
+        assert_eq!(nvme.read_bytes_per_sec, 0);
+
+        write_diskstats(&diskstats, 1500, 4000);
+        let nvme = &reader.read()[0];
+        assert_eq!(nvme.total_reads, 1500);
+        assert!(nvme.read_bytes_per_sec > 0);
+        assert!(nvme.read_iops > 0.0);
+        assert_eq!(nvme.write_bytes_per_sec, 0);
+    }
+
+    #[test]
+    fn parses_the_counter_columns() {
+        let line = " 8       0 sda 11 2 33 4 55 6 77 8 0 9 10";
+        let (name, counters) = parse_line(line).unwrap();
+        assert_eq!(name, "sda");
+        assert_eq!(
+            counters,
+            DiskCounters {
+                reads: 11,
+                sectors_read: 33,
+                writes: 55,
+                sectors_written: 77,
+            }
+        );
+        assert!(parse_line("8 0 sda").is_none());
+    }
+}
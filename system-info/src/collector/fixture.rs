@@ -0,0 +1,29 @@
+use std::fs;
+use std::path::Path;
+
+/// Creates `dir` and writes sysfs-style attribute files into it, each ending in a
+/// newline as the kernel prints them.
+pub fn write_attributes(dir: &Path, files: &[(&str, &str)]) {
+    fs::create_dir_all(dir).unwrap();
+    for (name, contents) in files {
+        fs::write(dir.join(name), format!("{}\n", contents)).unwrap();
+    }
+}
+
+/// Adds a DRM `card<n>` for the PCI function in `slot` to a fake `/sys/class/drm`,
+/// with `files` in its `device` directory and a `driver` link named after `driver`.
+#[cfg(unix)]
+pub fn drm_card(root: &Path, name: &str, slot: &str, driver: &str, files: &[(&str, &str)]) {
+    let device = root.join(name).join("device");
+    write_attributes(&device, files);
+    write_attributes(
+        &device,
+        &[(
+            "uevent",
+            &format!("DRIVER={}\nPCI_SLOT_NAME={}", driver, slot),
+        )],
+    );
+    let driver_dir = root.join("drivers").join(driver);
+    fs::create_dir_all(&driver_dir).unwrap();
+    std::os::unix::fs::symlink(&driver_dir, device.join("driver")).unwrap();
+}
@@ -0,0 +1,477 @@
+use std::time::Duration;
+
+use wgpu::{AdapterInfo, Backends, DeviceType, Instance};
+
+use super::nvml::{NvmlBackend, NvmlLibrary, NVIDIA_VENDOR_ID};
+use super::vram::{DrmCard, DrmVramReader};
+use super::{Collector, Sample};
+use crate::snapshot::{GpuDeviceType, GpuSnapshot};
+
+/// Reports the GPU adapters visible to wgpu, enriched with NVML telemetry for NVIDIA cards
+/// and DRM memory figures for other Linux drivers.
+///
+/// Adapter enumeration is expensive and the result rarely changes, so it runs once at
+/// construction and every sample reuses that list.
+pub struct GpuCollector {
+    gpus: Vec<GpuSnapshot>,
+    nvml: Option<Box<dyn NvmlBackend>>,
+    drm: DrmVramReader,
+    /// PCI slot of the DRM card behind each entry of `gpus`, where one was found.
+    drm_slots: Vec<Option<String>>,
+}
+
+impl GpuCollector {
+    pub fn new() -> Self {
+        Self::with_nvml(default_nvml_backend())
+    }
+
+    /// Builds a collector that reads vendor telemetry from `nvml`, if given.
+    pub fn with_nvml(nvml: Option<Box<dyn NvmlBackend>>) -> Self {
+        Self::with_adapters(enumerate_gpus(), nvml, DrmVramReader::default())
+    }
+
+    fn with_adapters(
+        gpus: Vec<GpuSnapshot>,
+        nvml: Option<Box<dyn NvmlBackend>>,
+        drm: DrmVramReader,
+    ) -> Self {
+        let drm_slots = assign_drm_slots(&gpus, drm.cards());
+        Self {
+            gpus,
+            nvml,
+            drm,
+            drm_slots,
+        }
+    }
+
+    /// Attaches NVML readings to the matching adapters, adding any device wgpu did not report.
+    fn apply_nvml(&self, gpus: &mut Vec<GpuSnapshot>) {
+        let Some(nvml) = &self.nvml else {
+            return;
+        };
+
+        let count = match nvml.device_count() {
+            Ok(count) => count,
+            Err(err) => {
+                log::warn!("Failed to query NVML device count: {}", err);
+                return;
+            }
+        };
+
+        for index in 0..count {
+            let device = match nvml.device(index) {
+                Ok(device) => device,
+                Err(err) => {
+                    log::warn!("Failed to read NVML device {}: {}", index, err);
+                    continue;
+                }
+            };
+
+            let matching = gpus.iter_mut().find(|gpu| {
+                gpu.vendor_id == NVIDIA_VENDOR_ID
+                    && gpu.device_id == device.device_id
+                    && gpu.telemetry.is_none()
+            });
+            match matching {
+                Some(gpu) => {
+                    gpu.telemetry = Some(device.telemetry);
+                    gpu.vram = device.vram;
+                }
+                None => gpus.push(GpuSnapshot {
+                    name: device.name,
+                    device_type: GpuDeviceType::Discrete,
+                    backends: Vec::new(),
+                    vendor_id: NVIDIA_VENDOR_ID,
+                    device_id: device.device_id,
+                    driver: "NVIDIA".to_string(),
+                    driver_version: String::new(),
+                    max_storage_buffer_binding_bytes: 0,
+                    vram: device.vram,
+                    telemetry: Some(device.telemetry),
+                }),
+            }
+        }
+    }
+}
+
+/// Pairs each adapter with a DRM card of the same vendor and device ID.
+///
+/// wgpu does not say which PCI slot an adapter sits in, so identical cards are paired in
+/// order: the first such adapter gets the card in the lowest slot, the next one the card
+/// after it. No two adapters share a card.
+fn assign_drm_slots(gpus: &[GpuSnapshot], mut cards: Vec<DrmCard>) -> Vec<Option<String>> {
+    gpus.iter()
+        .map(|gpu| {
+            let index = cards.iter().position(|card| {
+                card.vendor_id == gpu.vendor_id && card.device_id == gpu.device_id
+            })?;
+            Some(cards.remove(index).slot)
+        })
+        .collect()
+}
+
+/// The real driver's NVML, or `None` on machines without one.
+fn default_nvml_backend() -> Option<Box<dyn NvmlBackend>> {
+    match NvmlLibrary::init() {
+        Ok(nvml) => Some(Box::new(nvml)),
+        Err(err) => {
+            log::info!("NVML unavailable, GPU telemetry disabled: {}", err);
+            None
+        }
+    }
+}
+
+impl Collector for GpuCollector {
+    fn name(&self) -> &'static str {
+        "gpu"
+    }
+
+    fn interval(&self) -> Duration {
+        Duration::from_secs(2)
+    }
+
+    fn collect(&mut self) -> Sample {
+        let mut gpus = self.gpus.clone();
+        self.apply_nvml(&mut gpus);
+
+        // Anything NVML did not cover may still be described by the kernel driver. NVML
+        // only appends entries, so `drm_slots` still lines up with the adapters.
+        for (gpu, slot) in gpus.iter_mut().zip(&self.drm_slots) {
+            if let (None, Some(slot)) = (&gpu.vram, slot) {
+                gpu.vram = self.drm.read(slot);
+            }
+        }
+
+        Sample::Gpu(gpus)
+    }
+}
+
+/// Enumerates every physical GPU visible to wgpu.
+///
+/// The same device usually shows up once per backend (Vulkan, GL, DX12, ...), so adapters
+/// are merged by PCI identity and each entry records the backends that expose it.
+fn enumerate_gpus() -> Vec<GpuSnapshot> {
+    let instance = Instance::default();
+    let mut gpus: Vec<GpuSnapshot> = Vec::new();
+
+    for adapter in instance.enumerate_adapters(Backends::all()) {
+        // Only a shader binding limit; real VRAM figures come from NVML or the DRM driver
+        let limit = u64::from(adapter.limits().max_storage_buffer_binding_size);
+        merge_adapter(&mut gpus, adapter.get_info(), limit);
+    }
+
+    gpus
+}
+
+/// Adds an adapter to the list, or records its backend on the entry it duplicates.
+///
+/// wgpu reports neither a PCI bus id nor a LUID, so two identical cards look the same.
+/// A backend lists each card once, in a stable order, so an entry never takes a second
+/// adapter from a backend it already has: the second card on Vulkan pairs with the
+/// second card on GL rather than collapsing into the first.
+fn merge_adapter(gpus: &mut Vec<GpuSnapshot>, info: AdapterInfo, storage_buffer_limit: u64) {
+    let backend = format!("{:?}", info.backend);
+
+    let duplicate = gpus
+        .iter_mut()
+        .find(|gpu| !gpu.backends.contains(&backend) && is_same_device(gpu, &info));
+    if let Some(gpu) = duplicate {
+        gpu.backends.push(backend);
+        // Some backends leave the driver fields empty; keep the first descriptive ones
+        if gpu.driver.is_empty() {
+            gpu.driver = info.driver;
+        }
+        if gpu.driver_version.is_empty() {
+            gpu.driver_version = info.driver_info;
+        }
+        return;
+    }
+
+    gpus.push(GpuSnapshot {
+        name: info.name,
+        device_type: device_type(info.device_type),
+        backends: vec![backend],
+        vendor_id: info.vendor,
+        device_id: info.device,
+        driver: info.driver,
+        driver_version: info.driver_info,
+        max_storage_buffer_binding_bytes: storage_buffer_limit,
+        vram: None,
+        telemetry: None,
+    });
+}
+
+/// Decides whether an adapter describes the same kind of GPU as a listed one.
+fn is_same_device(gpu: &GpuSnapshot, info: &AdapterInfo) -> bool {
+    if gpu.vendor_id != info.vendor {
+        return false;
+    }
+
+    // Backends such as GL know the vendor but not the device ID, and software adapters
+    // know neither, so fall back to the adapter name, which GL decorates with a suffix.
+    // An empty name is a prefix of everything and proves nothing.
+    if gpu.device_id == 0 || info.device == 0 {
+        if gpu.name.is_empty() || info.name.is_empty() {
+            return false;
+        }
+        return gpu.name.starts_with(&info.name) || info.name.starts_with(&gpu.name);
+    }
+
+    gpu.device_id == info.device
+}
+
+fn device_type(device_type: DeviceType) -> GpuDeviceType {
+    match device_type {
+        DeviceType::IntegratedGpu => GpuDeviceType::Integrated,
+        DeviceType::DiscreteGpu => GpuDeviceType::Discrete,
+        DeviceType::VirtualGpu => GpuDeviceType::Virtual,
+        DeviceType::Cpu => GpuDeviceType::Cpu,
+        DeviceType::Other => GpuDeviceType::Other,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::nvml::{MockNvml, NvmlDevice};
+    use super::*;
+    #[cfg(unix)]
+    use crate::collector::fixture::drm_card;
+    use crate::snapshot::{GpuTelemetry, VramSource};
+
+    fn adapter(name: &str, vendor_id: u32, device_id: u32) -> GpuSnapshot {
+        GpuSnapshot {
+            name: name.to_string(),
+            device_type: GpuDeviceType::Discrete,
+            backends: vec!["Vulkan".to_string()],
+            vendor_id,
+            device_id,
+            driver: String::new(),
+            driver_version: String::new(),
+            max_storage_buffer_binding_bytes: 1 << 30,
+            vram: None,
+            telemetry: None,
+        }
+    }
+
+    fn merged(adapters: Vec<GpuSnapshot>, nvml: MockNvml) -> Vec<GpuSnapshot> {
+        let collector = GpuCollector::with_adapters(
+            adapters.clone(),
+            Some(Box::new(nvml)),
+            DrmVramReader::default(),
+        );
+        let mut gpus = adapters;
+        collector.apply_nvml(&mut gpus);
+        gpus
+    }
+
+    #[test]
+    fn nvml_readings_attach_to_the_matching_adapter() {
+        let gpus = merged(
+            vec![
+                adapter("Intel UHD Graphics 770", 0x8086, 0x4680),
+                adapter("NVIDIA GeForce RTX 3070", NVIDIA_VENDOR_ID, 0x2484),
+            ],
+            MockNvml::sample(),
+        );
+
+        assert_eq!(gpus.len(), 2);
+        assert!(gpus[0].telemetry.is_none());
+        let nvidia = &gpus[1];
+        assert_eq!(nvidia.backends, ["Vulkan"]);
+        let telemetry = nvidia.telemetry.as_ref().unwrap();
+        assert_eq!(telemetry.utilization_percent, Some(42));
+        assert_eq!(telemetry.temperature_celsius, Some(61));
+        let vram = nvidia.vram.as_ref().unwrap();
+        assert_eq!(vram.total_bytes, 8 * 1024 * 1024 * 1024);
+        assert_eq!(vram.source, VramSource::Nvml);
+    }
+
+    #[test]
+    fn nvml_devices_without_an_adapter_are_added() {
+        let gpus = merged(
+            vec![adapter("Intel UHD Graphics 770", 0x8086, 0x4680)],
+            MockNvml::sample(),
+        );
+
+        assert_eq!(gpus.len(), 2);
+        let nvidia = &gpus[1];
+        assert_eq!(nvidia.name, "Mock NVIDIA GeForce RTX 3070");
+        assert_eq!(
+            (nvidia.vendor_id, nvidia.device_id),
+            (NVIDIA_VENDOR_ID, 0x2484)
+        );
+        assert!(nvidia.backends.is_empty());
+        assert!(nvidia.telemetry.is_some());
+    }
+
+    #[test]
+    fn each_nvml_device_claims_a_separate_adapter() {
+        let card = |utilization| NvmlDevice {
+            name: "NVIDIA GeForce RTX 3070".to_string(),
+            device_id: 0x2484,
+            telemetry: GpuTelemetry {
+                utilization_percent: Some(utilization),
+                ..Default::default()
+            },
+            vram: None,
+        };
+        let gpus = merged(
+            vec![
+                adapter("NVIDIA GeForce RTX 3070", NVIDIA_VENDOR_ID, 0x2484),
+                adapter("NVIDIA GeForce RTX 3070", NVIDIA_VENDOR_ID, 0x2484),
+            ],
+            MockNvml::new(vec![card(10), card(90)]),
+        );
+
+        let utilization: Vec<_> = gpus
+            .iter()
+            .map(|gpu| gpu.telemetry.as_ref().unwrap().utilization_percent)
+            .collect();
+        assert_eq!(utilization, [Some(10), Some(90)]);
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn collect_fills_in_nvml_and_drm_readings() {
+        let sysfs = tempfile::tempdir().unwrap();
+        let debugfs = tempfile::tempdir().unwrap();
+        drm_card(
+            sysfs.path(),
+            "card0",
+            "0000:03:00.0",
+            "amdgpu",
+            &[
+                ("vendor", "0x1002"),
+                ("device", "0x73bf"),
+                ("mem_info_vram_total", "17163091968"),
+                ("mem_info_vram_used", "1073741824"),
+            ],
+        );
+        let mut collector = GpuCollector::with_adapters(
+            vec![
+                adapter("AMD Radeon RX 6800", 0x1002, 0x73bf),
+                adapter("NVIDIA GeForce RTX 3070", NVIDIA_VENDOR_ID, 0x2484),
+                adapter("Intel UHD Graphics 770", 0x8086, 0x4680),
+            ],
+            Some(Box::new(MockNvml::sample())),
+            DrmVramReader::new(sysfs.path(), debugfs.path()),
+        );
+
+        let Sample::Gpu(gpus) = collector.collect() else {
+            panic!("GPU collector returned another kind of sample");
+        };
+        assert_eq!(gpus.len(), 3);
+
+        let amd = gpus[0].vram.as_ref().unwrap();
+        assert_eq!(amd.source, VramSource::AmdgpuSysfs);
+        assert_eq!(amd.used_bytes, Some(1_073_741_824));
+        assert!(gpus[0].telemetry.is_none());
+
+        assert_eq!(gpus[1].vram.as_ref().unwrap().source, VramSource::Nvml);
+        assert_eq!(
+            gpus[1].telemetry.as_ref().unwrap().power_draw_milliwatts,
+            Some(142_000)
+        );
+
+        // No card in the fixture tree and no debugfs entry
+        assert!(gpus[2].vram.is_none());
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn identical_cards_read_the_vram_of_separate_slots() {
+        let sysfs = tempfile::tempdir().unwrap();
+        for (card, slot, used) in [
+            ("card0", "0000:0a:00.0", "2147483648"),
+            ("card1", "0000:03:00.0", "1073741824"),
+        ] {
+            drm_card(
+                sysfs.path(),
+                card,
+                slot,
+                "amdgpu",
+                &[
+                    ("vendor", "0x1002"),
+                    ("device", "0x73bf"),
+                    ("mem_info_vram_total", "17163091968"),
+                    ("mem_info_vram_used", used),
+                ],
+            );
+        }
+        let mut collector = GpuCollector::with_adapters(
+            vec![
+                adapter("AMD Radeon RX 6800", 0x1002, 0x73bf),
+                adapter("AMD Radeon RX 6800", 0x1002, 0x73bf),
+            ],
+            None,
+            DrmVramReader::new(sysfs.path(), sysfs.path().join("debug")),
+        );
+
+        let Sample::Gpu(gpus) = collector.collect() else {
+            panic!("GPU collector returned another kind of sample");
+        };
+        let used: Vec<_> = gpus
+            .iter()
+            .map(|gpu| gpu.vram.as_ref().unwrap().used_bytes)
+            .collect();
+        assert_eq!(used, [Some(1_073_741_824), Some(2_147_483_648)]);
+    }
+
+    fn adapter_info(name: &str, device: u32, backend: wgpu::Backend) -> AdapterInfo {
+        AdapterInfo {
+            name: name.to_string(),
+            vendor: NVIDIA_VENDOR_ID,
+            device,
+            device_type: DeviceType::DiscreteGpu,
+            driver: String::new(),
+            driver_info: String::new(),
+            backend,
+        }
+    }
+
+    #[test]
+    fn identical_cards_stay_separate_across_backends() {
+        use wgpu::Backend::{Gl, Vulkan};
+
+        let mut gpus = Vec::new();
+        for info in [
+            adapter_info("NVIDIA GeForce RTX 3070", 0x2484, Vulkan),
+            adapter_info("NVIDIA GeForce RTX 3070", 0x2484, Vulkan),
+            adapter_info("NVIDIA GeForce RTX 3070/PCIe/SSE2", 0, Gl),
+            adapter_info("NVIDIA GeForce RTX 3070/PCIe/SSE2", 0, Gl),
+        ] {
+            merge_adapter(&mut gpus, info, 0);
+        }
+
+        assert_eq!(gpus.len(), 2);
+        for gpu in &gpus {
+            assert_eq!(gpu.backends, ["Vulkan", "Gl"]);
+        }
+    }
+
+    #[test]
+    fn an_empty_adapter_name_matches_nothing() {
+        let mut gpus = Vec::new();
+        merge_adapter(
+            &mut gpus,
+            adapter_info("NVIDIA GeForce RTX 3070", 0x2484, wgpu::Backend::Vulkan),
+            0,
+        );
+        merge_adapter(&mut gpus, adapter_info("", 0, wgpu::Backend::Gl), 0);
+
+        assert_eq!(gpus.len(), 2);
+        assert_eq!(gpus[0].backends, ["Vulkan"]);
+    }
+
+    #[test]
+    fn without_nvml_adapters_are_left_alone() {
+        let adapters = vec![adapter("NVIDIA GeForce RTX 3070", NVIDIA_VENDOR_ID, 0x2484)];
+        let collector =
+            GpuCollector::with_adapters(adapters.clone(), None, DrmVramReader::default());
+        let mut gpus = adapters;
+        collector.apply_nvml(&mut gpus);
+
+        assert_eq!(gpus.len(), 1);
+        assert!(gpus[0].telemetry.is_none());
+    }
+}
@@ -0,0 +1,186 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::snapshot::{SensorKind, SensorReading};
+
+/// Reads fan, voltage and power sensors from the Linux hwmon class.
+///
+/// Temperatures are left to sysinfo, which already reads them from the same
+/// chips. Tests point `root` at a fixture tree.
+pub struct HwmonReader {
+    root: PathBuf,
+}
+
+impl Default for HwmonReader {
+    fn default() -> Self {
+        Self::new("/sys/class/hwmon")
+    }
+}
+
+/// Attribute prefix, sysfs unit divisor and kind of every channel type read here.
+const CHANNELS: [(&str, f64, SensorKind); 3] = [
+    ("fan", 1.0, SensorKind::Fan),             // RPM
+    ("in", 1_000.0, SensorKind::Voltage),      // millivolts
+    ("power", 1_000_000.0, SensorKind::Power), // microwatts
+];
+
+impl HwmonReader {
+    pub fn new(root: impl Into<PathBuf>) -> Self {
+        Self { root: root.into() }
+    }
+
+    /// Every readable channel of every chip, ordered by chip and channel.
+    pub fn read(&self) -> Vec<SensorReading> {
+        let Ok(entries) = fs::read_dir(&self.root) else {
+            return Vec::new(); // Not Linux, or sysfs is not mounted
+        };
+        let mut chips: Vec<PathBuf> = entries.flatten().map(|entry| entry.path()).collect();
+        chips.sort();
+
+        chips.iter().flat_map(|chip| read_chip(chip)).collect()
+    }
+}
+
+fn read_chip(chip: &Path) -> Vec<SensorReading> {
+    let chip_name = read_string(&chip.join("name")).unwrap_or_else(|| {
+        chip.file_name()
+            .map(|name| name.to_string_lossy().into_owned())
+            .unwrap_or_default()
+    });
+
+    let Ok(entries) = fs::read_dir(chip) else {
+        return Vec::new();
+    };
+    // Channels are discovered through their `<prefix><n>_input` files, or `_average`
+    // for power meters that only report a mean. The second field ranks `_input` first.
+    let mut channels: Vec<(usize, u32, u8, String)> = entries
+        .flatten()
+        .filter_map(|entry| {
+            let file = entry.file_name().to_string_lossy().into_owned();
+            let (channel, rank) = match file.strip_suffix("_input") {
+                Some(channel) => (channel, 0),
+                None => (file.strip_suffix("_average")?, 1),
+            };
+            CHANNELS
+                .iter()
+                .enumerate()
+                .find_map(|(kind, (prefix, _, _))| {
+                    let index = channel.strip_prefix(prefix)?.parse::<u32>().ok()?;
+                    Some((kind, index, rank, file.clone()))
+                })
+        })
+        .collect();
+    channels.sort();
+    // A channel with both keeps its instantaneous `_input` reading
+    channels.dedup_by_key(|(kind, index, _, _)| (*kind, *index));
+
+    channels
+        .into_iter()
+        .filter_map(|(kind, index, _, file)| {
+            let (prefix, divisor, kind) = CHANNELS[kind];
+            let attribute = |suffix: &str| {
+                read_f64(&chip.join(format!("{}{}_{}", prefix, index, suffix)))
+                    .map(|value| value / divisor)
+            };
+            let label = read_string(&chip.join(format!("{}{}_label", prefix, index)))
+                .unwrap_or_else(|| format!("{}{}", prefix, index));
+
+            Some(SensorReading {
+                label: format!("{} {}", chip_name, label),
+                kind,
+                value: read_f64(&chip.join(file))? / divisor,
+                // A zero minimum means the driver has no lower limit configured
+                min: attribute("min").filter(|min| *min > 0.0),
+                max: attribute("max").or_else(|| attribute("cap")),
+                critical: attribute("crit"),
+            })
+        })
+        .collect()
+}
+
+fn read_string(path: &Path) -> Option<String> {
+    Some(fs::read_to_string(path).ok()?.trim().to_string())
+}
+
+fn read_f64(path: &Path) -> Option<f64> {
+    read_string(path)?.parse().ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::collector::fixture::write_attributes;
+
+    #[test]
+    fn reads_labelled_fans_voltages_and_power_from_a_fixture_tree() {
+        let root = tempfile::tempdir().unwrap();
+        write_attributes(
+            &root.path().join("hwmon0"),
+            &[
+                ("name", "nct6798"),
+                ("fan1_input", "1200"),
+                ("fan1_label", "CPU Fan"),
+                ("fan1_min", "0"),
+                ("in0_input", "1024"),
+                ("in0_min", "900"),
+                ("in0_max", "1100"),
+                // Temperatures come from sysinfo, not from here
+                ("temp1_input", "45000"),
+                ("temp1_label", "SYSTIN"),
+            ],
+        );
+        // A chip without a `name` file is labelled after its directory
+        write_attributes(
+            &root.path().join("hwmon1"),
+            &[
+                ("power1_input", "12000000"),
+                ("power1_average", "15000000"),
+                ("power1_cap", "65000000"),
+                ("power1_crit", "80000000"),
+            ],
+        );
+
+        let readings = HwmonReader::new(root.path()).read();
+        let labels: Vec<&str> = readings.iter().map(|r| r.label.as_str()).collect();
+        assert_eq!(labels, ["nct6798 CPU Fan", "nct6798 in0", "hwmon1 power1"]);
+
+        let fan = &readings[0];
+        assert_eq!(fan.kind, SensorKind::Fan);
+        assert_eq!((fan.value, fan.min), (1200.0, None));
+
+        let voltage = &readings[1];
+        assert_eq!(voltage.kind, SensorKind::Voltage);
+        assert_eq!(
+            (voltage.value, voltage.min, voltage.max),
+            (1.024, Some(0.9), Some(1.1))
+        );
+
+        let power = &readings[2];
+        assert_eq!(power.kind, SensorKind::Power);
+        assert_eq!(
+            (power.value, power.max, power.critical),
+            (12.0, Some(65.0), Some(80.0))
+        );
+    }
+
+    #[test]
+    fn falls_back_to_the_average_when_there_is_no_input() {
+        let root = tempfile::tempdir().unwrap();
+        write_attributes(
+            &root.path().join("hwmon0"),
+            &[("name", "amdgpu"), ("power1_average", "35000000")],
+        );
+
+        let readings = HwmonReader::new(root.path()).read();
+        assert_eq!(readings.len(), 1);
+        assert_eq!(readings[0].value, 35.0);
+    }
+
+    #[test]
+    fn a_missing_root_yields_nothing() {
+        let root = tempfile::tempdir().unwrap();
+        assert!(HwmonReader::new(root.path().join("absent"))
+            .read()
+            .is_empty());
+    }
+}
@@ -0,0 +1,55 @@
+use std::collections::HashMap;
+
+/// IPv4 and IPv6 addresses assigned to each interface, keyed by interface name.
+#[cfg(unix)]
+pub fn interface_addresses() -> HashMap<String, Vec<String>> {
+    use std::ffi::CStr;
+    use std::net::{Ipv4Addr, Ipv6Addr};
+
+    let mut addresses: HashMap<String, Vec<String>> = HashMap::new();
+    let mut list: *mut libc::ifaddrs = std::ptr::null_mut();
+    // SAFETY: getifaddrs fills `list` with a linked list that stays valid until freeifaddrs
+    if unsafe { libc::getifaddrs(&mut list) } != 0 {
+        log::debug!("getifaddrs failed: {}", std::io::Error::last_os_error());
+        return addresses;
+    }
+
+    let mut cursor = list;
+    while !cursor.is_null() {
+        // SAFETY: `cursor` is a non-null node of the list returned above
+        let entry = unsafe { &*cursor };
+        cursor = entry.ifa_next;
+        if entry.ifa_addr.is_null() || entry.ifa_name.is_null() {
+            continue;
+        }
+
+        // SAFETY: the family field tells which sockaddr variant `ifa_addr` points to
+        let address = unsafe {
+            match i32::from((*entry.ifa_addr).sa_family) {
+                libc::AF_INET => {
+                    let addr = &*(entry.ifa_addr as *const libc::sockaddr_in);
+                    Ipv4Addr::from(u32::from_be(addr.sin_addr.s_addr)).to_string()
+                }
+                libc::AF_INET6 => {
+                    let addr = &*(entry.ifa_addr as *const libc::sockaddr_in6);
+                    Ipv6Addr::from(addr.sin6_addr.s6_addr).to_string()
+                }
+                _ => continue, // Link-layer entries; the MAC comes from sysinfo
+            }
+        };
+        // SAFETY: ifa_name is a NUL-terminated string owned by the list
+        let name = unsafe { CStr::from_ptr(entry.ifa_name) }
+            .to_string_lossy()
+            .into_owned();
+        addresses.entry(name).or_default().push(address);
+    }
+
+    // SAFETY: `list` came from a successful getifaddrs and is freed exactly once
+    unsafe { libc::freeifaddrs(list) };
+    addresses
+}
+
+#[cfg(not(unix))]
+pub fn interface_addresses() -> HashMap<String, Vec<String>> {
+    HashMap::new()
+}
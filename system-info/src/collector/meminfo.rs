@@ -0,0 +1,106 @@
+use std::collections::HashMap;
+use std::fs;
+use std::path::PathBuf;
+
+use crate::snapshot::MemoryBreakdown;
+
+/// Reads the detailed memory breakdown from `/proc/meminfo`, or any file in its format.
+pub struct MeminfoReader {
+    path: PathBuf,
+}
+
+impl Default for MeminfoReader {
+    fn default() -> Self {
+        Self::new("/proc/meminfo")
+    }
+}
+
+impl MeminfoReader {
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self { path: path.into() }
+    }
+
+    /// `None` when the file is missing, as on non-Linux systems.
+    pub fn read(&self) -> Option<MemoryBreakdown> {
+        let text = fs::read_to_string(&self.path).ok()?;
+        let fields = parse(&text);
+        let bytes = |key: &str| fields.get(key).copied().unwrap_or(0);
+
+        Some(MemoryBreakdown {
+            available_bytes: bytes("MemAvailable"),
+            buffers_bytes: bytes("Buffers"),
+            cached_bytes: bytes("Cached"),
+            shared_bytes: bytes("Shmem"),
+            slab_bytes: bytes("Slab"),
+            dirty_bytes: bytes("Dirty"),
+            writeback_bytes: bytes("Writeback"),
+            huge_pages_total: bytes("HugePages_Total"),
+            huge_pages_free: bytes("HugePages_Free"),
+            huge_page_size_bytes: bytes("Hugepagesize"),
+        })
+    }
+}
+
+/// Parses `Key:   value [kB]` lines, converting kB values to bytes.
+/// Page counts such as `HugePages_Total` carry no unit and are kept as they are.
+fn parse(text: &str) -> HashMap<&str, u64> {
+    text.lines()
+        .filter_map(|line| {
+            let (key, rest) = line.split_once(':')?;
+            let mut parts = rest.split_whitespace();
+            let value: u64 = parts.next()?.parse().ok()?;
+            let multiplier = match parts.next() {
+                Some("kB") => 1024,
+                _ => 1,
+            };
+            Some((key.trim(), value * multiplier))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MEMINFO: &str = "\
+MemTotal:       32768000 kB
+MemFree:         1024000 kB
+MemAvailable:   16384000 kB
+Buffers:          512000 kB
+Cached:          8192000 kB
+Shmem:            256000 kB
+Slab:             128000 kB
+Dirty:              1000 kB
+Writeback:             0 kB
+HugePages_Total:      16
+HugePages_Free:        4
+Hugepagesize:       2048 kB
+";
+
+    #[test]
+    fn converts_kilobytes_and_keeps_page_counts() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("meminfo");
+        fs::write(&path, MEMINFO).unwrap();
+
+        let breakdown = MeminfoReader::new(&path).read().unwrap();
+        assert_eq!(breakdown.available_bytes, 16_384_000 * 1024);
+        assert_eq!(breakdown.buffers_bytes, 512_000 * 1024);
+        assert_eq!(breakdown.cached_bytes, 8_192_000 * 1024);
+        assert_eq!(breakdown.shared_bytes, 256_000 * 1024);
+        assert_eq!(breakdown.slab_bytes, 128_000 * 1024);
+        assert_eq!(breakdown.dirty_bytes, 1_000 * 1024);
+        assert_eq!(breakdown.writeback_bytes, 0);
+        assert_eq!(breakdown.huge_pages_total, 16);
+        assert_eq!(breakdown.huge_pages_free, 4);
+        assert_eq!(breakdown.huge_page_size_bytes, 2048 * 1024);
+    }
+
+    #[test]
+    fn a_missing_file_yields_none() {
+        let dir = tempfile::tempdir().unwrap();
+        assert!(MeminfoReader::new(dir.path().join("meminfo"))
+            .read()
+            .is_none());
+    }
+}
@@ -0,0 +1,45 @@
+use std::time::Duration;
+
+use sysinfo::{System, SystemExt};
+
+use super::meminfo::MeminfoReader;
+use super::{Collector, Sample};
+use crate::snapshot::MemorySnapshot;
+
+/// Samples physical memory and swap usage.
+pub struct MemoryCollector {
+    system: System,
+    meminfo: MeminfoReader,
+}
+
+impl MemoryCollector {
+    pub fn new() -> Self {
+        Self {
+            system: System::new(),
+            meminfo: MeminfoReader::default(),
+        }
+    }
+}
+
+impl Collector for MemoryCollector {
+    fn name(&self) -> &'static str {
+        "memory"
+    }
+
+    fn interval(&self) -> Duration {
+        Duration::from_secs(1)
+    }
+
+    fn collect(&mut self) -> Sample {
+        self.system.refresh_memory();
+
+        Sample::Memory(MemorySnapshot {
+            total_bytes: self.system.total_memory(),
+            used_bytes: self.system.used_memory(),
+            free_bytes: self.system.free_memory(),
+            swap_total_bytes: self.system.total_swap(),
+            swap_used_bytes: self.system.used_swap(),
+            breakdown: self.meminfo.read(),
+        })
+    }
+}
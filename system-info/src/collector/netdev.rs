@@ -0,0 +1,98 @@
+use std::collections::HashMap;
+use std::fs;
+use std::path::PathBuf;
+
+/// Error and drop totals of one interface from `/proc/net/dev`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct NetDevCounters {
+    pub receive_errors: u64,
+    pub receive_drops: u64,
+    pub transmit_errors: u64,
+    pub transmit_drops: u64,
+}
+
+/// Reads per-interface counters from `/proc/net/dev` or a copy of it.
+pub struct NetDevReader {
+    path: PathBuf,
+}
+
+impl Default for NetDevReader {
+    fn default() -> Self {
+        Self::new("/proc/net/dev")
+    }
+}
+
+impl NetDevReader {
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self { path: path.into() }
+    }
+
+    /// Counters keyed by interface name; empty when the file is missing, as on non-Linux systems.
+    pub fn read(&self) -> HashMap<String, NetDevCounters> {
+        fs::read_to_string(&self.path)
+            .map(|text| text.lines().filter_map(parse_line).collect())
+            .unwrap_or_default()
+    }
+}
+
+/// Parses `name: rx_bytes rx_packets rx_errs rx_drop ... tx_bytes tx_packets tx_errs tx_drop ...`.
+/// The two header lines have no colon-separated counters and are skipped.
+fn parse_line(line: &str) -> Option<(String, NetDevCounters)> {
+    let (name, counters) = line.split_once(':')?;
+    let fields: Vec<u64> = counters
+        .split_whitespace()
+        .map(str::parse)
+        .collect::<Result<_, _>>()
+        .ok()?;
+    if fields.len() < 16 {
+        return None;
+    }
+
+    Some((
+        name.trim().to_string(),
+        NetDevCounters {
+            receive_errors: fields[2],
+            receive_drops: fields[3],
+            transmit_errors: fields[10],
+            transmit_drops: fields[11],
+        },
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const NET_DEV: &str = "\
+Inter-|   Receive                                                |  Transmit
+ face |bytes    packets errs drop fifo frame compressed multicast|bytes    packets errs drop fifo colls carrier compressed
+    lo: 4620344   41232    0    0    0     0          0         0  4620344   41232    0    0    0     0       0          0
+  eth0: 987654321 812345   3   17    0     0          0      1024 123456789 456789    5    2    0     0       0          0
+";
+
+    #[test]
+    fn reads_error_and_drop_counters_per_interface() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("dev");
+        fs::write(&path, NET_DEV).unwrap();
+
+        let counters = NetDevReader::new(&path).read();
+        assert_eq!(counters.len(), 2);
+        assert_eq!(counters["lo"], NetDevCounters::default());
+        assert_eq!(
+            counters["eth0"],
+            NetDevCounters {
+                receive_errors: 3,
+                receive_drops: 17,
+                transmit_errors: 5,
+                transmit_drops: 2,
+            }
+        );
+    }
+
+    #[test]
+    fn a_missing_file_yields_no_counters() {
+        let dir = tempfile::tempdir().unwrap();
+        assert!(NetDevReader::new(dir.path().join("dev")).read().is_empty());
+    }
+}
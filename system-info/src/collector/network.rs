@@ -0,0 +1,80 @@
+use std::time::{Duration, Instant};
+
+use sysinfo::{NetworkExt, NetworksExt, System, SystemExt};
+
+use super::ifaddrs::interface_addresses;
+use super::netdev::NetDevReader;
+use super::{Collector, Sample};
+use crate::snapshot::InterfaceSnapshot;
+
+/// Samples throughput, error counters and addresses of every network interface.
+pub struct NetworkCollector {
+    system: System,
+    netdev: NetDevReader,
+    last_refresh: Instant,
+}
+
+impl NetworkCollector {
+    pub fn new() -> Self {
+        let mut system = System::new();
+        // sysinfo reports traffic since the last listing, so the first one starts the clock
+        system.refresh_networks_list();
+        Self {
+            system,
+            netdev: NetDevReader::default(),
+            last_refresh: Instant::now(),
+        }
+    }
+}
+
+impl Collector for NetworkCollector {
+    fn name(&self) -> &'static str {
+        "network"
+    }
+
+    fn interval(&self) -> Duration {
+        Duration::from_secs(1)
+    }
+
+    fn collect(&mut self) -> Sample {
+        // Re-list rather than refresh so interfaces that come up later are picked up
+        self.system.refresh_networks_list();
+        let elapsed = self.last_refresh.elapsed().as_secs_f64().max(f64::EPSILON);
+        self.last_refresh = Instant::now();
+
+        let mut addresses = interface_addresses();
+        let netdev = self.netdev.read();
+
+        let mut interfaces: Vec<InterfaceSnapshot> = self
+            .system
+            .networks()
+            .iter()
+            .map(|(name, data)| {
+                let counters = netdev.get(name);
+                let per_second = |delta: u64| delta as f64 / elapsed;
+
+                InterfaceSnapshot {
+                    name: name.clone(),
+                    mac_address: data.mac_address().to_string(),
+                    addresses: addresses.remove(name).unwrap_or_default(),
+                    received_bytes_per_sec: per_second(data.received()) as u64,
+                    transmitted_bytes_per_sec: per_second(data.transmitted()) as u64,
+                    received_packets_per_sec: per_second(data.packets_received()) as f32,
+                    transmitted_packets_per_sec: per_second(data.packets_transmitted()) as f32,
+                    total_received_bytes: data.total_received(),
+                    total_transmitted_bytes: data.total_transmitted(),
+                    // /proc/net/dev is authoritative on Linux; sysinfo covers the other platforms
+                    receive_errors: counters
+                        .map_or(data.total_errors_on_received(), |c| c.receive_errors),
+                    transmit_errors: counters
+                        .map_or(data.total_errors_on_transmitted(), |c| c.transmit_errors),
+                    receive_drops: counters.map(|c| c.receive_drops),
+                    transmit_drops: counters.map(|c| c.transmit_drops),
+                }
+            })
+            .collect();
+
+        interfaces.sort_by(|a, b| a.name.cmp(&b.name));
+        Sample::Network(interfaces)
+    }
+}
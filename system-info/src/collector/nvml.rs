@@ -0,0 +1,127 @@
+use nvml_wrapper::enum_wrappers::device::{Clock, TemperatureSensor};
+use nvml_wrapper::error::NvmlError;
+use nvml_wrapper::Nvml;
+
+use crate::snapshot::{GpuTelemetry, VramSource, VramUsage};
+
+/// PCI vendor ID assigned to NVIDIA.
+pub const NVIDIA_VENDOR_ID: u32 = 0x10de;
+
+/// Identity and live readings for one device managed by NVML.
+#[derive(Debug, Clone, Default)]
+pub struct NvmlDevice {
+    pub name: String,
+    /// PCI device ID, used to match the device with a wgpu adapter.
+    pub device_id: u32,
+    pub telemetry: GpuTelemetry,
+    pub vram: Option<VramUsage>,
+}
+
+/// The subset of NVML the GPU collector relies on, so it can run against a fake library.
+pub trait NvmlBackend: Send {
+    fn device_count(&self) -> Result<u32, NvmlError>;
+
+    fn device(&self, index: u32) -> Result<NvmlDevice, NvmlError>;
+}
+
+/// Backend that talks to the real NVIDIA driver.
+pub struct NvmlLibrary {
+    nvml: Nvml,
+}
+
+impl NvmlLibrary {
+    /// Loads the NVML shared library; fails on machines without an NVIDIA driver.
+    pub fn init() -> Result<Self, NvmlError> {
+        Ok(Self {
+            nvml: Nvml::init()?,
+        })
+    }
+}
+
+impl NvmlBackend for NvmlLibrary {
+    fn device_count(&self) -> Result<u32, NvmlError> {
+        self.nvml.device_count()
+    }
+
+    fn device(&self, index: u32) -> Result<NvmlDevice, NvmlError> {
+        let device = self.nvml.device_by_index(index)?;
+
+        // The upper 16 bits hold the device ID, the lower 16 bits the vendor ID
+        let device_id = device.pci_info()?.pci_device_id >> 16;
+
+        let utilization = device.utilization_rates().ok();
+        let memory = device.memory_info().ok();
+        let fan_speed_percent = match device.num_fans() {
+            Ok(fans) if fans > 0 => device.fan_speed(0).ok(),
+            _ => None,
+        };
+
+        Ok(NvmlDevice {
+            name: device.name()?,
+            device_id,
+            telemetry: GpuTelemetry {
+                utilization_percent: utilization.as_ref().map(|rates| rates.gpu),
+                memory_utilization_percent: utilization.as_ref().map(|rates| rates.memory),
+                graphics_clock_mhz: device.clock_info(Clock::Graphics).ok(),
+                memory_clock_mhz: device.clock_info(Clock::Memory).ok(),
+                temperature_celsius: device.temperature(TemperatureSensor::Gpu).ok(),
+                fan_speed_percent,
+                power_draw_milliwatts: device.power_usage().ok(),
+            },
+            vram: memory.map(|info| VramUsage {
+                total_bytes: info.total,
+                used_bytes: Some(info.used),
+                source: VramSource::Nvml,
+            }),
+        })
+    }
+}
+
+/// In-memory stand-in for NVML that reports a fixed set of devices.
+#[cfg(test)]
+pub struct MockNvml {
+    devices: Vec<NvmlDevice>,
+}
+
+#[cfg(test)]
+impl MockNvml {
+    pub fn new(devices: Vec<NvmlDevice>) -> Self {
+        Self { devices }
+    }
+
+    /// A single mid-range card under moderate load.
+    pub fn sample() -> Self {
+        Self::new(vec![NvmlDevice {
+            name: "Mock NVIDIA GeForce RTX 3070".to_string(),
+            device_id: 0x2484,
+            telemetry: GpuTelemetry {
+                utilization_percent: Some(42),
+                memory_utilization_percent: Some(17),
+                graphics_clock_mhz: Some(1725),
+                memory_clock_mhz: Some(7000),
+                temperature_celsius: Some(61),
+                fan_speed_percent: Some(38),
+                power_draw_milliwatts: Some(142_000),
+            },
+            vram: Some(VramUsage {
+                total_bytes: 8 * 1024 * 1024 * 1024,
+                used_bytes: Some(3 * 1024 * 1024 * 1024),
+                source: VramSource::Nvml,
+            }),
+        }])
+    }
+}
+
+#[cfg(test)]
+impl NvmlBackend for MockNvml {
+    fn device_count(&self) -> Result<u32, NvmlError> {
+        Ok(self.devices.len() as u32)
+    }
+
+    fn device(&self, index: u32) -> Result<NvmlDevice, NvmlError> {
+        self.devices
+            .get(index as usize)
+            .cloned()
+            .ok_or(NvmlError::InvalidArg)
+    }
+}
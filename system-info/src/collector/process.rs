@@ -0,0 +1,71 @@
+use std::time::{Duration, Instant};
+
+use sysinfo::{PidExt, ProcessExt, System, SystemExt, UserExt};
+
+use super::{Collector, Sample};
+use crate::snapshot::ProcessSnapshot;
+
+/// Samples the process table.
+pub struct ProcessCollector {
+    system: System,
+    last_refresh: Instant,
+}
+
+impl ProcessCollector {
+    pub fn new() -> Self {
+        let mut system = System::new();
+        system.refresh_users_list();
+        // Per-process CPU time and disk I/O are diffed between refreshes, so load a baseline
+        system.refresh_processes();
+        Self {
+            system,
+            last_refresh: Instant::now(),
+        }
+    }
+}
+
+impl Collector for ProcessCollector {
+    fn name(&self) -> &'static str {
+        "processes"
+    }
+
+    fn interval(&self) -> Duration {
+        Duration::from_secs(2)
+    }
+
+    fn collect(&mut self) -> Sample {
+        self.system.refresh_processes();
+        let elapsed = self.last_refresh.elapsed().as_secs_f64().max(f64::EPSILON);
+        self.last_refresh = Instant::now();
+
+        let processes = self
+            .system
+            .processes()
+            .values()
+            .map(|process| {
+                let disk = process.disk_usage();
+                let user = process
+                    .user_id()
+                    .and_then(|uid| self.system.get_user_by_id(uid))
+                    .map(|user| user.name().to_string())
+                    .unwrap_or_default();
+
+                ProcessSnapshot {
+                    pid: process.pid().as_u32(),
+                    parent_pid: process.parent().map(|pid| pid.as_u32()),
+                    name: process.name().to_string(),
+                    user,
+                    cpu_percent: process.cpu_usage(),
+                    memory_bytes: process.memory(),
+                    disk_read_bytes_per_sec: (disk.read_bytes as f64 / elapsed) as u64,
+                    disk_write_bytes_per_sec: (disk.written_bytes as f64 / elapsed) as u64,
+                    status: process.status().to_string(),
+                    start_time: process.start_time(),
+                    command: process.cmd().join(" "),
+                }
+            })
+            .collect();
+
+        Sample::Processes(processes)
+    }
+}
@@ -0,0 +1,56 @@
+use std::time::Duration;
+
+use sysinfo::{ComponentExt, System, SystemExt};
+
+use super::hwmon::HwmonReader;
+use super::{Collector, Sample};
+use crate::snapshot::{SensorKind, SensorReading};
+
+/// Samples temperature, fan, voltage and power sensors.
+pub struct SensorCollector {
+    system: System,
+    hwmon: HwmonReader,
+}
+
+impl SensorCollector {
+    pub fn new() -> Self {
+        let mut system = System::new();
+        system.refresh_components_list();
+        Self {
+            system,
+            hwmon: HwmonReader::default(),
+        }
+    }
+}
+
+impl Collector for SensorCollector {
+    fn name(&self) -> &'static str {
+        "sensors"
+    }
+
+    fn interval(&self) -> Duration {
+        Duration::from_secs(2)
+    }
+
+    fn collect(&mut self) -> Sample {
+        self.system.refresh_components();
+
+        let mut sensors: Vec<SensorReading> = self
+            .system
+            .components()
+            .iter()
+            .map(|component| SensorReading {
+                label: component.label().to_string(),
+                kind: SensorKind::Temperature,
+                value: f64::from(component.temperature()),
+                min: None,
+                // sysinfo's `max` is the hottest reading seen so far, not a limit
+                max: None,
+                critical: component.critical().map(f64::from),
+            })
+            .collect();
+        sensors.extend(self.hwmon.read());
+
+        Sample::Sensors(sensors)
+    }
+}
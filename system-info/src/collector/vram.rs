@@ -0,0 +1,283 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::snapshot::{VramSource, VramUsage};
+
+/// Reads dedicated VRAM figures from the Linux DRM interfaces.
+///
+/// amdgpu exposes them in sysfs; i915 and xe only publish them through debugfs,
+/// which is normally readable by root alone.
+pub struct DrmVramReader {
+    sysfs_drm_root: PathBuf,
+    debugfs_dri_root: PathBuf,
+}
+
+/// A primary DRM node and the PCI function that drives it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DrmCard {
+    /// PCI slot name such as `0000:03:00.0`; unlike the ID pair it tells identical cards apart.
+    pub slot: String,
+    pub vendor_id: u32,
+    pub device_id: u32,
+    minor: u32,
+}
+
+impl Default for DrmVramReader {
+    fn default() -> Self {
+        Self::new("/sys/class/drm", "/sys/kernel/debug/dri")
+    }
+}
+
+impl DrmVramReader {
+    pub fn new(sysfs_drm_root: impl Into<PathBuf>, debugfs_dri_root: impl Into<PathBuf>) -> Self {
+        Self {
+            sysfs_drm_root: sysfs_drm_root.into(),
+            debugfs_dri_root: debugfs_dri_root.into(),
+        }
+    }
+
+    /// Lists the primary DRM cards, ordered by PCI slot.
+    pub fn cards(&self) -> Vec<DrmCard> {
+        let Ok(entries) = fs::read_dir(&self.sysfs_drm_root) else {
+            return Vec::new();
+        };
+
+        let mut cards: Vec<DrmCard> = entries
+            .flatten()
+            .filter_map(|entry| {
+                let name = entry.file_name().to_string_lossy().into_owned();
+                // Only primary nodes like `card0`; `card0-DP-1` and friends are connectors
+                let minor = name.strip_prefix("card")?.parse::<u32>().ok()?;
+                let device = entry.path().join("device");
+                Some(DrmCard {
+                    slot: pci_slot(&device)?,
+                    vendor_id: read_hex(&device.join("vendor"))?,
+                    device_id: read_hex(&device.join("device"))?,
+                    minor,
+                })
+            })
+            .collect();
+        cards.sort_by(|a, b| a.slot.cmp(&b.slot));
+        cards
+    }
+
+    /// Reads the VRAM usage of the card in the given PCI slot.
+    pub fn read(&self, slot: &str) -> Option<VramUsage> {
+        let card = self.cards().into_iter().find(|card| card.slot == slot)?;
+        let device = self
+            .sysfs_drm_root
+            .join(format!("card{}", card.minor))
+            .join("device");
+
+        match driver_name(&device).as_deref() {
+            Some("amdgpu") => read_amdgpu(&device),
+            Some("i915") => self.read_i915(card.minor),
+            Some("xe") => self.read_xe(card.minor),
+            _ => None,
+        }
+    }
+
+    fn read_i915(&self, minor: u32) -> Option<VramUsage> {
+        let text = fs::read_to_string(
+            self.debugfs_dri_root
+                .join(minor.to_string())
+                .join("i915_gem_objects"),
+        )
+        .ok()?;
+        // Device-local memory regions are named `local0`, `local1`, ...; system memory comes first
+        let local = &text[text.find("local")?..];
+        let (total_bytes, free_bytes) = parse_total_and_free(local)?;
+
+        Some(VramUsage {
+            total_bytes,
+            used_bytes: free_bytes.map(|free| total_bytes.saturating_sub(free)),
+            source: VramSource::I915Debugfs,
+        })
+    }
+
+    fn read_xe(&self, minor: u32) -> Option<VramUsage> {
+        let text = fs::read_to_string(
+            self.debugfs_dri_root
+                .join(minor.to_string())
+                .join("vram0_mm"),
+        )
+        .ok()?;
+        let (total_bytes, free_bytes) = parse_total_and_free(&text)?;
+
+        Some(VramUsage {
+            total_bytes,
+            used_bytes: free_bytes.map(|free| total_bytes.saturating_sub(free)),
+            source: VramSource::XeDebugfs,
+        })
+    }
+}
+
+fn read_amdgpu(device: &Path) -> Option<VramUsage> {
+    let total_bytes = read_u64(&device.join("mem_info_vram_total"))?;
+
+    Some(VramUsage {
+        total_bytes,
+        used_bytes: read_u64(&device.join("mem_info_vram_used")),
+        source: VramSource::AmdgpuSysfs,
+    })
+}
+
+/// PCI slot of a card's device, from `PCI_SLOT_NAME` in its uevent or else from the
+/// name of the directory its `device` symlink points at.
+fn pci_slot(device: &Path) -> Option<String> {
+    let from_uevent = fs::read_to_string(device.join("uevent"))
+        .ok()
+        .and_then(|uevent| {
+            uevent
+                .lines()
+                .find_map(|line| line.strip_prefix("PCI_SLOT_NAME="))
+                .map(str::to_string)
+        });
+    from_uevent.or_else(|| {
+        let target = fs::read_link(device).ok()?;
+        Some(target.file_name()?.to_string_lossy().into_owned())
+    })
+}
+
+/// Name of the kernel driver bound to a PCI device, from its `driver` symlink.
+fn driver_name(device: &Path) -> Option<String> {
+    let target = fs::read_link(device.join("driver")).ok()?;
+    Some(target.file_name()?.to_string_lossy().into_owned())
+}
+
+fn read_u64(path: &Path) -> Option<u64> {
+    fs::read_to_string(path).ok()?.trim().parse().ok()
+}
+
+/// Reads sysfs IDs such as `0x1002`.
+fn read_hex(path: &Path) -> Option<u32> {
+    let text = fs::read_to_string(path).ok()?;
+    u32::from_str_radix(text.trim().trim_start_matches("0x"), 16).ok()
+}
+
+/// Extracts the first `total:` value and the first `free:`/`available:` value after it.
+fn parse_total_and_free(text: &str) -> Option<(u64, Option<u64>)> {
+    let after_total = &text[text.find("total:")? + "total:".len()..];
+    let total = parse_size(after_total)?;
+
+    let free = ["free:", "available:"]
+        .iter()
+        .filter_map(|key| {
+            after_total
+                .find(key)
+                .map(|pos| &after_total[pos + key.len()..])
+        })
+        .filter_map(parse_size)
+        .next();
+
+    Some((total, free))
+}
+
+/// Parses a leading size such as `0x1f8000000`, `17163091968 bytes` or `16384MiB`.
+fn parse_size(text: &str) -> Option<u64> {
+    let text = text.trim_start();
+    let end = text
+        .find(|c: char| !c.is_ascii_alphanumeric())
+        .unwrap_or(text.len());
+    let token = &text[..end];
+
+    if let Some(hex) = token.strip_prefix("0x") {
+        return u64::from_str_radix(hex, 16).ok();
+    }
+
+    let digits_end = token
+        .find(|c: char| !c.is_ascii_digit())
+        .unwrap_or(token.len());
+    let value: u64 = token[..digits_end].parse().ok()?;
+    let multiplier = match &token[digits_end..] {
+        "" => 1,
+        "KiB" => 1024,
+        "MiB" => 1024 * 1024,
+        "GiB" => 1024 * 1024 * 1024,
+        _ => return None,
+    };
+
+    value.checked_mul(multiplier)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[cfg(unix)]
+    use crate::collector::fixture::{drm_card, write_attributes};
+
+    #[test]
+    #[cfg(unix)]
+    fn reads_amdgpu_vram_for_the_card_in_each_slot() {
+        let sysfs = tempfile::tempdir().unwrap();
+        let debugfs = tempfile::tempdir().unwrap();
+        // Two identical cards, listed out of slot order
+        drm_card(
+            sysfs.path(),
+            "card0",
+            "0000:0a:00.0",
+            "amdgpu",
+            &[
+                ("vendor", "0x1002"),
+                ("device", "0x73bf"),
+                ("mem_info_vram_total", "17163091968"),
+                ("mem_info_vram_used", "2147483648"),
+            ],
+        );
+        drm_card(
+            sysfs.path(),
+            "card1",
+            "0000:03:00.0",
+            "amdgpu",
+            &[
+                ("vendor", "0x1002"),
+                ("device", "0x73bf"),
+                ("mem_info_vram_total", "17163091968"),
+                ("mem_info_vram_used", "1073741824"),
+            ],
+        );
+        // Connector nodes share the card's device and must be skipped
+        write_attributes(&sysfs.path().join("card0-DP-1"), &[]);
+
+        let reader = DrmVramReader::new(sysfs.path(), debugfs.path());
+        let slots: Vec<_> = reader.cards().into_iter().map(|card| card.slot).collect();
+        assert_eq!(slots, ["0000:03:00.0", "0000:0a:00.0"]);
+
+        let first = reader.read("0000:03:00.0").unwrap();
+        assert_eq!(first.total_bytes, 17_163_091_968);
+        assert_eq!(first.used_bytes, Some(1_073_741_824));
+        assert_eq!(first.source, VramSource::AmdgpuSysfs);
+        let second = reader.read("0000:0a:00.0").unwrap();
+        assert_eq!(second.used_bytes, Some(2_147_483_648));
+
+        assert!(reader.read("0000:01:00.0").is_none());
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn falls_back_to_the_device_link_for_the_slot() {
+        let sysfs = tempfile::tempdir().unwrap();
+        let pci = sysfs.path().join("devices").join("0000:03:00.0");
+        write_attributes(&pci, &[("vendor", "0x8086"), ("device", "0x56a0")]);
+        fs::create_dir_all(sysfs.path().join("card0")).unwrap();
+        std::os::unix::fs::symlink(&pci, sysfs.path().join("card0").join("device")).unwrap();
+
+        let reader = DrmVramReader::new(sysfs.path(), sysfs.path().join("debug"));
+        let cards = reader.cards();
+        assert_eq!(cards.len(), 1);
+        assert_eq!(cards[0].slot, "0000:03:00.0");
+        assert_eq!((cards[0].vendor_id, cards[0].device_id), (0x8086, 0x56a0));
+    }
+
+    #[test]
+    fn parses_debugfs_sizes() {
+        let text = "local0: total:16384MiB, available: 0x200000000\n";
+        assert_eq!(
+            parse_total_and_free(text),
+            Some((16384 * 1024 * 1024, Some(0x2_0000_0000)))
+        );
+        assert_eq!(parse_size("17163091968 bytes"), Some(17_163_091_968));
+        assert_eq!(parse_size("12furlongs"), None);
+        assert_eq!(parse_size("18446744073709551615GiB"), None);
+    }
+}
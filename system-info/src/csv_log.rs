@@ -0,0 +1,464 @@
+use std::collections::HashMap;
+use std::error::Error;
+use std::fmt;
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, BufRead, BufReader, Write};
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use chrono::{DateTime, Local, NaiveDate};
+use tokio::task;
+use tokio::time::{self, MissedTickBehavior};
+
+use crate::monitor::Monitor;
+use crate::recorder;
+use crate::snapshot::SystemSnapshot;
+
+/// Recorded metrics that become columns, one per instance.
+const METRICS: [&str; 12] = [
+    "cpu_usage_percent",
+    "memory_total_bytes",
+    "memory_used_bytes",
+    "swap_total_bytes",
+    "swap_used_bytes",
+    "gpu_vram_total_bytes",
+    "gpu_vram_used_bytes",
+    "gpu_utilization_percent",
+    "disk_read_bytes_per_second",
+    "disk_written_bytes_per_second",
+    "network_received_bytes_per_second",
+    "network_transmitted_bytes_per_second",
+];
+
+/// When the log moves on to a new file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Rotation {
+    /// At the first row of each local day.
+    Daily,
+    /// Once the file has reached this many bytes.
+    Size(u64),
+}
+
+impl FromStr for Rotation {
+    type Err = String;
+
+    /// Parses `daily` or a size such as `500KB`, `100MB` or `1G`, in multiples of 1024.
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        if value.eq_ignore_ascii_case("daily") {
+            return Ok(Rotation::Daily);
+        }
+
+        let invalid = || format!("expected 'daily' or a size such as 100MB, got '{}'", value);
+        let split = value
+            .find(|c: char| !c.is_ascii_digit())
+            .unwrap_or(value.len());
+        let (number, unit) = value.split_at(split);
+        let number: u64 = number.parse().map_err(|_| invalid())?;
+        let multiplier: u64 = match unit.trim().to_ascii_uppercase().as_str() {
+            "" | "B" => 1,
+            "K" | "KB" | "KIB" => 1024,
+            "M" | "MB" | "MIB" => 1024 * 1024,
+            "G" | "GB" | "GIB" => 1024 * 1024 * 1024,
+            _ => return Err(invalid()),
+        };
+        match number.checked_mul(multiplier) {
+            Some(bytes) if bytes > 0 => Ok(Rotation::Size(bytes)),
+            _ => Err(invalid()),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum CsvLogError {
+    Open(PathBuf, io::Error),
+    /// The file exists but does not start with a header this log wrote.
+    NotALog(PathBuf),
+    Write(PathBuf, io::Error),
+}
+
+impl fmt::Display for CsvLogError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CsvLogError::Open(path, err) => {
+                write!(f, "could not open CSV log {}: {}", path.display(), err)
+            }
+            CsvLogError::NotALog(path) => {
+                write!(
+                    f,
+                    "{} exists but is not a system-info CSV log",
+                    path.display()
+                )
+            }
+            CsvLogError::Write(path, err) => {
+                write!(f, "could not write CSV log {}: {}", path.display(), err)
+            }
+        }
+    }
+}
+
+impl Error for CsvLogError {}
+
+/// Appends one row per snapshot to a CSV file, rotating it by day or size.
+pub struct CsvLog {
+    path: PathBuf,
+    rotation: Option<Rotation>,
+    file: File,
+    /// Fixed by the first snapshot, or by the header of an existing file, so rows
+    /// line up for the life of the log even as devices come and go.
+    columns: Vec<String>,
+    size: u64,
+    /// Local date of the newest row in the current file.
+    day: NaiveDate,
+}
+
+impl CsvLog {
+    /// Opens the log at `path`, continuing an existing file with its own columns.
+    pub fn open(path: &Path, rotation: Option<Rotation>) -> Result<Self, CsvLogError> {
+        let open_err = |err| CsvLogError::Open(path.to_path_buf(), err);
+        let file = open_append(path).map_err(open_err)?;
+        let metadata = file.metadata().map_err(open_err)?;
+
+        let mut columns = Vec::new();
+        if metadata.len() > 0 {
+            let mut header = String::new();
+            BufReader::new(File::open(path).map_err(open_err)?)
+                .read_line(&mut header)
+                .map_err(open_err)?;
+            columns = split_record(header.trim_end_matches(['\r', '\n']));
+            if columns.first().map(String::as_str) != Some("timestamp") {
+                return Err(CsvLogError::NotALog(path.to_path_buf()));
+            }
+            columns.remove(0);
+        }
+
+        let modified = metadata.modified().map_err(open_err)?;
+        Ok(Self {
+            path: path.to_path_buf(),
+            rotation,
+            file,
+            columns,
+            size: metadata.len(),
+            day: DateTime::<Local>::from(modified).date_naive(),
+        })
+    }
+
+    /// Appends a row for `snapshot`, starting a new file first if rotation is due.
+    pub fn write(&mut self, snapshot: &SystemSnapshot) -> Result<(), CsvLogError> {
+        let time = DateTime::<Local>::from(snapshot.timestamp);
+        if self.columns.is_empty() {
+            self.columns = columns(snapshot);
+        }
+
+        let rotate = match self.rotation {
+            Some(Rotation::Daily) => self.size > 0 && time.date_naive() != self.day,
+            Some(Rotation::Size(max)) => self.size >= max,
+            None => false,
+        };
+        if rotate {
+            let label = match self.rotation {
+                Some(Rotation::Daily) => self.day.format("%Y-%m-%d").to_string(),
+                _ => time.format("%Y-%m-%d-%H%M%S").to_string(),
+            };
+            self.rotate(&label)
+                .map_err(|err| CsvLogError::Write(self.path.clone(), err))?;
+        }
+
+        let mut text = String::new();
+        if self.size == 0 {
+            let header: Vec<&str> = std::iter::once("timestamp")
+                .chain(self.columns.iter().map(String::as_str))
+                .collect();
+            text.push_str(&join_record(&header));
+        }
+
+        let values: HashMap<String, f64> = recorder::readings(snapshot)
+            .into_iter()
+            .map(|reading| {
+                (
+                    column_name(reading.metric, &reading.instance),
+                    reading.value,
+                )
+            })
+            .collect();
+        let timestamp = time.format("%Y-%m-%d %H:%M:%S").to_string();
+        let row: Vec<String> = std::iter::once(timestamp)
+            .chain(self.columns.iter().map(|column| {
+                values
+                    .get(column)
+                    .map(|value| format_value(*value))
+                    .unwrap_or_default()
+            }))
+            .collect();
+        text.push_str(&join_record(&row));
+
+        // One write per row, so an interrupted run never leaves half a line behind
+        self.file
+            .write_all(text.as_bytes())
+            .map_err(|err| CsvLogError::Write(self.path.clone(), err))?;
+        self.size += text.len() as u64;
+        self.day = time.date_naive();
+        Ok(())
+    }
+
+    /// Moves the current file aside as `<stem>-<label>.<ext>` and starts an empty one.
+    fn rotate(&mut self, label: &str) -> io::Result<()> {
+        let stem = self
+            .path
+            .file_stem()
+            .unwrap_or_default()
+            .to_string_lossy()
+            .into_owned();
+        let extension = self
+            .path
+            .extension()
+            .map(|extension| format!(".{}", extension.to_string_lossy()))
+            .unwrap_or_default();
+
+        let mut target = self
+            .path
+            .with_file_name(format!("{}-{}{}", stem, label, extension));
+        let mut attempt = 1;
+        while target.exists() {
+            target = self
+                .path
+                .with_file_name(format!("{}-{}.{}{}", stem, label, attempt, extension));
+            attempt += 1;
+        }
+
+        fs::rename(&self.path, &target)?;
+        log::info!("Rotated CSV log to {}", target.display());
+        self.file = open_append(&self.path)?;
+        self.size = 0;
+        Ok(())
+    }
+}
+
+/// Runs the collectors that are due every `interval` and appends the snapshot to the
+/// log until interrupted. Collectors not yet due contribute their latest values.
+pub async fn run(
+    mut log: CsvLog,
+    monitor: Arc<Mutex<Monitor>>,
+    interval: Duration,
+) -> Result<(), Box<dyn Error>> {
+    if interval.is_zero() {
+        return Err("--interval must be longer than zero".into());
+    }
+
+    let mut ticks = time::interval(interval);
+    ticks.set_missed_tick_behavior(MissedTickBehavior::Delay);
+    // The first tick is immediate; usage and rates need a full interval after the baseline
+    ticks.tick().await;
+
+    loop {
+        ticks.tick().await;
+        // Collectors block on procfs, sysfs and NVML, so poll them off the runtime workers
+        let snapshot = task::spawn_blocking({
+            let monitor = Arc::clone(&monitor);
+            move || {
+                let mut monitor = monitor.lock().ok()?;
+                monitor.poll(Instant::now());
+                Some(monitor.snapshot().clone())
+            }
+        })
+        .await?;
+        let Some(snapshot) = snapshot else {
+            return Ok(());
+        };
+        log.write(&snapshot)?;
+    }
+}
+
+fn open_append(path: &Path) -> io::Result<File> {
+    OpenOptions::new().create(true).append(true).open(path)
+}
+
+/// Column names for everything `snapshot` reports, in the order the recorder lists them.
+fn columns(snapshot: &SystemSnapshot) -> Vec<String> {
+    recorder::readings(snapshot)
+        .into_iter()
+        .filter(|reading| METRICS.contains(&reading.metric))
+        .map(|reading| column_name(reading.metric, &reading.instance))
+        .collect()
+}
+
+/// `metric` for machine-wide values, `metric[instance]` for per-device ones.
+fn column_name(metric: &str, instance: &str) -> String {
+    if instance.is_empty() {
+        metric.to_string()
+    } else {
+        format!("{}[{}]", metric, instance)
+    }
+}
+
+/// Whole numbers as integers, everything else to two decimal places.
+fn format_value(value: f64) -> String {
+    if value.fract() == 0.0 {
+        format!("{:.0}", value)
+    } else {
+        format!("{:.2}", value)
+    }
+}
+
+/// Joins fields into one CSV line, quoting those that need it.
+fn join_record<S: AsRef<str>>(fields: &[S]) -> String {
+    let fields: Vec<String> = fields
+        .iter()
+        .map(|field| {
+            let field = field.as_ref();
+            if field.contains([',', '"', '\n', '\r']) {
+                format!("\"{}\"", field.replace('"', "\"\""))
+            } else {
+                field.to_string()
+            }
+        })
+        .collect();
+    format!("{}\n", fields.join(","))
+}
+
+/// Splits one CSV line into fields, undoing [`join_record`]'s quoting.
+fn split_record(line: &str) -> Vec<String> {
+    let mut fields = vec![String::new()];
+    let mut quoted = false;
+    let mut chars = line.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '"' if quoted && chars.peek() == Some(&'"') => {
+                chars.next();
+                fields.last_mut().unwrap().push('"');
+            }
+            '"' => quoted = !quoted,
+            ',' if !quoted => fields.push(String::new()),
+            c => fields.last_mut().unwrap().push(c),
+        }
+    }
+    fields
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::SystemTime;
+
+    use chrono::TimeZone;
+
+    use super::*;
+    use crate::snapshot::CoreSnapshot;
+
+    const HEADER: &str = "timestamp,cpu_usage_percent,memory_total_bytes,memory_used_bytes,\
+                          swap_total_bytes,swap_used_bytes\n";
+
+    fn at(day: u32, hour: u32, second: u32) -> SystemTime {
+        Local
+            .with_ymd_and_hms(2024, 1, day, hour, 0, second)
+            .unwrap()
+            .into()
+    }
+
+    fn snapshot(timestamp: SystemTime, cpu_usage: f32) -> SystemSnapshot {
+        let mut snapshot = SystemSnapshot::new();
+        snapshot.timestamp = timestamp;
+        snapshot.cpu.usage_percent = cpu_usage;
+        snapshot
+    }
+
+    fn files(dir: &Path) -> Vec<String> {
+        let mut names: Vec<String> = fs::read_dir(dir)
+            .unwrap()
+            .map(|entry| entry.unwrap().file_name().to_string_lossy().into_owned())
+            .collect();
+        names.sort();
+        names
+    }
+
+    #[test]
+    fn rotation_parses_daily_and_sizes() {
+        assert_eq!("daily".parse(), Ok(Rotation::Daily));
+        assert_eq!("Daily".parse(), Ok(Rotation::Daily));
+        assert_eq!("100".parse(), Ok(Rotation::Size(100)));
+        assert_eq!("500KB".parse(), Ok(Rotation::Size(500 * 1024)));
+        assert_eq!("10 mb".parse(), Ok(Rotation::Size(10 * 1024 * 1024)));
+        assert_eq!("1G".parse(), Ok(Rotation::Size(1024 * 1024 * 1024)));
+
+        for invalid in ["", "0", "MB", "5TB", "1.5MB", "hourly", "99999999999999G"] {
+            assert!(invalid.parse::<Rotation>().is_err(), "{}", invalid);
+        }
+    }
+
+    #[test]
+    fn records_round_trip_through_quoting() {
+        let fields = [
+            "plain",
+            "",
+            "with,comma",
+            "with \"quotes\"",
+            "\"",
+            "both, \"of them\"",
+        ];
+        let line = join_record(&fields);
+        assert_eq!(
+            line,
+            "plain,,\"with,comma\",\"with \"\"quotes\"\"\",\"\"\"\",\"both, \"\"of them\"\"\"\n"
+        );
+        assert_eq!(split_record(line.trim_end_matches('\n')), fields);
+    }
+
+    #[test]
+    fn size_rotation_moves_the_full_file_aside() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("log.csv");
+        let mut log = CsvLog::open(&path, Some(Rotation::Size(1))).unwrap();
+
+        log.write(&snapshot(at(1, 12, 0), 25.0)).unwrap();
+        log.write(&snapshot(at(1, 12, 1), 50.5)).unwrap();
+
+        assert_eq!(files(dir.path()), ["log-2024-01-01-120001.csv", "log.csv"]);
+        assert_eq!(
+            fs::read_to_string(dir.path().join("log-2024-01-01-120001.csv")).unwrap(),
+            format!("{}2024-01-01 12:00:00,25,0,0,0,0\n", HEADER)
+        );
+        assert_eq!(
+            fs::read_to_string(&path).unwrap(),
+            format!("{}2024-01-01 12:00:01,50.50,0,0,0,0\n", HEADER)
+        );
+    }
+
+    #[test]
+    fn daily_rotation_of_a_continued_log_keeps_its_columns() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("log.csv");
+        let mut log = CsvLog::open(&path, Some(Rotation::Daily)).unwrap();
+        log.write(&snapshot(at(1, 12, 0), 25.0)).unwrap();
+        drop(log);
+        // As if the row had just been written, so the file dates from the first day
+        File::options()
+            .write(true)
+            .open(&path)
+            .unwrap()
+            .set_modified(at(1, 12, 0))
+            .unwrap();
+
+        // The second day brings a core the header does not have
+        let mut log = CsvLog::open(&path, Some(Rotation::Daily)).unwrap();
+        let mut next = snapshot(at(2, 8, 0), 75.0);
+        next.cpu.cores.push(CoreSnapshot {
+            name: "cpu0".to_string(),
+            ..Default::default()
+        });
+        log.write(&next).unwrap();
+        log.write(&snapshot(at(2, 9, 0), 80.0)).unwrap();
+
+        assert_eq!(files(dir.path()), ["log-2024-01-01.csv", "log.csv"]);
+        assert_eq!(
+            fs::read_to_string(dir.path().join("log-2024-01-01.csv")).unwrap(),
+            format!("{}2024-01-01 12:00:00,25,0,0,0,0\n", HEADER)
+        );
+        assert_eq!(
+            fs::read_to_string(&path).unwrap(),
+            format!(
+                "{}2024-01-02 08:00:00,75,0,0,0,0\n2024-01-02 09:00:00,80,0,0,0,0\n",
+                HEADER
+            )
+        );
+    }
+}
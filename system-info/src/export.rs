@@ -0,0 +1,231 @@
+use std::fmt;
+use std::time::SystemTime;
+
+use chrono::{DateTime, SecondsFormat, Utc};
+use clap::ValueEnum;
+use serde::{Serialize, Serializer};
+
+use crate::snapshot::SystemSnapshot;
+
+/// Version of the exported document layout, described in `docs/snapshot-schema.md`.
+///
+/// Bumped whenever a field is renamed, removed or changes meaning. Adding a field
+/// does not bump it, so consumers should ignore fields they do not know.
+pub const SCHEMA_VERSION: u32 = 1;
+
+/// Serialization formats offered on the command line and in the UI.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum Format {
+    Json,
+    Yaml,
+    Toml,
+}
+
+/// Failure to serialize a snapshot.
+#[derive(Debug)]
+pub enum ExportError {
+    Json(serde_json::Error),
+    Yaml(serde_yaml::Error),
+    Toml(toml::ser::Error),
+}
+
+impl fmt::Display for ExportError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ExportError::Json(err) => write!(f, "could not write JSON: {}", err),
+            ExportError::Yaml(err) => write!(f, "could not write YAML: {}", err),
+            ExportError::Toml(err) => write!(f, "could not write TOML: {}", err),
+        }
+    }
+}
+
+impl std::error::Error for ExportError {}
+
+/// Top-level exported document: the schema version and generator wrapped around the snapshot.
+#[derive(Serialize)]
+pub struct Document<'a> {
+    schema_version: u32,
+    generator: &'static str,
+    snapshot: &'a SystemSnapshot,
+}
+
+impl<'a> Document<'a> {
+    pub fn new(snapshot: &'a SystemSnapshot) -> Self {
+        Self {
+            schema_version: SCHEMA_VERSION,
+            generator: concat!("system-info ", env!("CARGO_PKG_VERSION")),
+            snapshot,
+        }
+    }
+}
+
+/// Serializes a snapshot into the given format.
+pub fn to_string(snapshot: &SystemSnapshot, format: Format) -> Result<String, ExportError> {
+    let document = Document::new(snapshot);
+
+    match format {
+        Format::Json => serde_json::to_string_pretty(&document).map_err(ExportError::Json),
+        Format::Yaml => serde_yaml::to_string(&document).map_err(ExportError::Yaml),
+        Format::Toml => toml::to_string_pretty(&document).map_err(ExportError::Toml),
+    }
+}
+
+/// Writes timestamps as RFC 3339 UTC strings rather than serde's seconds/nanos pair.
+pub fn serialize_timestamp<S: Serializer>(
+    timestamp: &SystemTime,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    let timestamp = DateTime::<Utc>::from(*timestamp).to_rfc3339_opts(SecondsFormat::Millis, true);
+    serializer.serialize_str(&timestamp)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use serde_json::Value;
+
+    use super::*;
+    use crate::snapshot::{
+        BlockDeviceSnapshot, CoreSnapshot, CpuSnapshot, GpuDeviceType, GpuSnapshot, GpuTelemetry,
+        InterfaceSnapshot, MemoryBreakdown, MemorySnapshot, MountSnapshot, ProcessSnapshot,
+        SensorKind, SensorReading, StorageSnapshot, VramSource, VramUsage,
+    };
+
+    /// A snapshot with every section filled, and with both set and unset optional fields.
+    fn populated() -> SystemSnapshot {
+        let gpu = |name: &str, vram, telemetry| GpuSnapshot {
+            name: name.to_string(),
+            device_type: GpuDeviceType::Discrete,
+            backends: vec!["Vulkan".to_string(), "Gl".to_string()],
+            vendor_id: 0x10de,
+            device_id: 0x2484,
+            driver: "NVIDIA".to_string(),
+            driver_version: "550.54".to_string(),
+            max_storage_buffer_binding_bytes: 1 << 31,
+            vram,
+            telemetry,
+        };
+
+        SystemSnapshot {
+            timestamp: SystemTime::UNIX_EPOCH + Duration::from_millis(1_714_564_800_250),
+            cpu: CpuSnapshot {
+                brand: "Test CPU".to_string(),
+                vendor_id: "GenuineIntel".to_string(),
+                usage_percent: 12.5,
+                cores: vec![CoreSnapshot {
+                    name: "cpu0".to_string(),
+                    usage_percent: 25.0,
+                    frequency_mhz: 3600,
+                }],
+            },
+            memory: MemorySnapshot {
+                total_bytes: 16 << 30,
+                used_bytes: 4 << 30,
+                free_bytes: 12 << 30,
+                breakdown: Some(MemoryBreakdown {
+                    cached_bytes: 1 << 30,
+                    ..Default::default()
+                }),
+                ..Default::default()
+            },
+            gpus: vec![
+                gpu(
+                    "NVIDIA GeForce RTX 3070",
+                    Some(VramUsage {
+                        total_bytes: 8 << 30,
+                        used_bytes: None,
+                        source: VramSource::Nvml,
+                    }),
+                    Some(GpuTelemetry {
+                        utilization_percent: Some(42),
+                        ..Default::default()
+                    }),
+                ),
+                gpu("Software Rasterizer", None, None),
+            ],
+            processes: vec![ProcessSnapshot {
+                pid: 42,
+                parent_pid: None,
+                name: "init".to_string(),
+                command: "/sbin/init splash".to_string(),
+                ..Default::default()
+            }],
+            storage: StorageSnapshot {
+                mounts: vec![MountSnapshot {
+                    device: "/dev/nvme0n1p2".to_string(),
+                    mount_point: "/".to_string(),
+                    file_system: "ext4".to_string(),
+                    total_bytes: 512 << 30,
+                    available_bytes: 100 << 30,
+                    is_removable: false,
+                }],
+                devices: vec![BlockDeviceSnapshot {
+                    name: "nvme0n1".to_string(),
+                    read_iops: 1.5,
+                    ..Default::default()
+                }],
+            },
+            networks: vec![InterfaceSnapshot {
+                name: "eth0".to_string(),
+                addresses: vec!["192.0.2.1/24".to_string()],
+                receive_drops: Some(3),
+                ..Default::default()
+            }],
+            sensors: vec![SensorReading {
+                label: "CPU Fan".to_string(),
+                kind: SensorKind::Fan,
+                value: 1200.0,
+                min: None,
+                max: Some(3000.0),
+                critical: None,
+            }],
+        }
+    }
+
+    /// TOML has no null, so unset fields are left out; drop them from the others to compare.
+    fn without_nulls(value: Value) -> Value {
+        match value {
+            Value::Object(map) => Value::Object(
+                map.into_iter()
+                    .filter(|(_, value)| !value.is_null())
+                    .map(|(key, value)| (key, without_nulls(value)))
+                    .collect(),
+            ),
+            Value::Array(items) => Value::Array(items.into_iter().map(without_nulls).collect()),
+            other => other,
+        }
+    }
+
+    #[test]
+    fn every_format_round_trips_the_same_document() {
+        let snapshot = populated();
+        let parse = |format| {
+            let text = to_string(&snapshot, format).unwrap();
+            let value = match format {
+                Format::Json => serde_json::from_str::<Value>(&text).unwrap(),
+                Format::Yaml => {
+                    serde_json::to_value(serde_yaml::from_str::<serde_yaml::Value>(&text).unwrap())
+                        .unwrap()
+                }
+                Format::Toml => {
+                    serde_json::to_value(toml::from_str::<toml::Value>(&text).unwrap()).unwrap()
+                }
+            };
+            without_nulls(value)
+        };
+
+        let json = parse(Format::Json);
+        assert_eq!(json["schema_version"], SCHEMA_VERSION);
+        assert_eq!(json["snapshot"]["timestamp"], "2024-05-01T12:00:00.250Z");
+        assert_eq!(
+            json["snapshot"]["gpus"][0]["vram"]["total_bytes"],
+            8u64 << 30
+        );
+        assert!(json["snapshot"]["gpus"][1].get("vram").is_none());
+        assert_eq!(json["snapshot"]["sensors"][0]["max"], 3000.0);
+
+        assert_eq!(parse(Format::Yaml), json);
+        assert_eq!(parse(Format::Toml), json);
+    }
+}
@@ -0,0 +1,20 @@
+use crate::snapshot::bytes_to_mb;
+
+/// Formats a byte rate in KB/s, or MB/s once it reaches a mebibyte per second.
+pub fn format_rate(bytes_per_sec: u64) -> String {
+    if bytes_per_sec >= 1024 * 1024 {
+        format!("{:.1} MB/s", bytes_per_sec as f64 / (1024.0 * 1024.0))
+    } else {
+        format!("{:.1} KB/s", bytes_per_sec as f64 / 1024.0)
+    }
+}
+
+/// Formats a capacity in MB below one gibibyte and in GB above it.
+pub fn format_size(bytes: u64) -> String {
+    const GIB: f64 = 1024.0 * 1024.0 * 1024.0;
+    if bytes as f64 >= GIB {
+        format!("{:.1} GB", bytes as f64 / GIB)
+    } else {
+        format!("{} MB", bytes_to_mb(bytes))
+    }
+}
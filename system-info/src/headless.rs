@@ -0,0 +1,224 @@
+use std::error::Error;
+use std::io::{self, Write};
+use std::time::Duration;
+
+use tokio::time;
+
+use crate::collector::CollectorRegistry;
+use crate::export::{self, Format};
+use crate::format::{format_rate, format_size};
+use crate::monitor::Monitor;
+use crate::snapshot::{SensorReading, SystemSnapshot};
+
+/// How long to let counters run before sampling, so usage and rates cover a real interval.
+const SAMPLE_WINDOW: Duration = Duration::from_secs(1);
+
+/// Number of processes listed, busiest first.
+const TOP_PROCESSES: usize = 10;
+
+/// Collects one snapshot with the same collectors as the UI and prints it to stdout,
+/// as a table or, when a format is given, as a versioned document.
+pub async fn run(format: Option<Format>) -> Result<(), Box<dyn Error>> {
+    let mut monitor = Monitor::new(CollectorRegistry::with_defaults());
+    // CPU usage and every rate are deltas against the baseline the collectors took when created
+    time::sleep(SAMPLE_WINDOW).await;
+    monitor.collect_all();
+
+    let mut stdout = io::stdout().lock();
+    match format {
+        Some(format) => writeln!(stdout, "{}", export::to_string(monitor.snapshot(), format)?)?,
+        None => write_report(&mut stdout, monitor.snapshot())?,
+    }
+    stdout.flush()?;
+    Ok(())
+}
+
+/// Writes a human-readable report with one table per section.
+pub fn write_report(out: &mut impl Write, snapshot: &SystemSnapshot) -> io::Result<()> {
+    let cpu = &snapshot.cpu;
+    writeln!(out, "CPU: {} ({})", cpu.brand, cpu.vendor_id)?;
+    writeln!(out, "Usage: {:.1}%", cpu.usage_percent)?;
+    write_table(
+        out,
+        &["Core", "Usage", "Frequency"],
+        cpu.cores.iter().map(|core| {
+            vec![
+                core.name.clone(),
+                format!("{:.1}%", core.usage_percent),
+                format!("{} MHz", core.frequency_mhz),
+            ]
+        }),
+    )?;
+
+    let memory = &snapshot.memory;
+    writeln!(out, "\nMemory")?;
+    let mut memory_rows = vec![vec![
+        "RAM".to_string(),
+        format_size(memory.total_bytes),
+        format_size(memory.used_bytes),
+        format!("{:.1}%", memory.used_percent()),
+    ]];
+    if let Some(swap_percent) = memory.swap_used_percent() {
+        memory_rows.push(vec![
+            "Swap".to_string(),
+            format_size(memory.swap_total_bytes),
+            format_size(memory.swap_used_bytes),
+            format!("{:.1}%", swap_percent),
+        ]);
+    }
+    write_table(out, &["", "Total", "Used", "Usage"], memory_rows)?;
+
+    writeln!(out, "\nGPUs")?;
+    write_table(
+        out,
+        &["Name", "Type", "Driver", "VRAM", "Load", "Temp"],
+        snapshot.gpus.iter().map(|gpu| {
+            let telemetry = gpu.telemetry.as_ref();
+            vec![
+                gpu.name.clone(),
+                gpu.device_type.to_string(),
+                gpu.driver.clone(),
+                gpu.vram
+                    .as_ref()
+                    .map(|vram| match vram.used_bytes {
+                        Some(used) => {
+                            format!("{} / {}", format_size(used), format_size(vram.total_bytes))
+                        }
+                        None => format_size(vram.total_bytes),
+                    })
+                    .unwrap_or_else(|| "N/A".to_string()),
+                optional(telemetry.and_then(|t| t.utilization_percent), "%"),
+                optional(telemetry.and_then(|t| t.temperature_celsius), " °C"),
+            ]
+        }),
+    )?;
+
+    writeln!(out, "\nFilesystems")?;
+    write_table(
+        out,
+        &["Mount", "Device", "Type", "Size", "Available", "Usage"],
+        snapshot.storage.mounts.iter().map(|mount| {
+            vec![
+                mount.mount_point.clone(),
+                mount.device.clone(),
+                mount.file_system.clone(),
+                format_size(mount.total_bytes),
+                format_size(mount.available_bytes),
+                format!("{:.0}%", mount.used_percent()),
+            ]
+        }),
+    )?;
+
+    writeln!(out, "\nBlock devices")?;
+    write_table(
+        out,
+        &["Device", "Read", "Write", "Read IOPS", "Write IOPS"],
+        snapshot.storage.devices.iter().map(|device| {
+            vec![
+                device.name.clone(),
+                format_rate(device.read_bytes_per_sec),
+                format_rate(device.write_bytes_per_sec),
+                format!("{:.0}", device.read_iops),
+                format!("{:.0}", device.write_iops),
+            ]
+        }),
+    )?;
+
+    writeln!(out, "\nNetwork")?;
+    write_table(
+        out,
+        &[
+            "Interface",
+            "Received",
+            "Transmitted",
+            "Errors",
+            "Addresses",
+        ],
+        snapshot.networks.iter().map(|interface| {
+            vec![
+                interface.name.clone(),
+                format_rate(interface.received_bytes_per_sec),
+                format_rate(interface.transmitted_bytes_per_sec),
+                (interface.receive_errors + interface.transmit_errors).to_string(),
+                interface.addresses.join(", "),
+            ]
+        }),
+    )?;
+
+    writeln!(out, "\nSensors")?;
+    write_table(
+        out,
+        &["Sensor", "Kind", "Value", "Critical"],
+        snapshot.sensors.iter().map(|sensor| {
+            vec![
+                sensor.label.clone(),
+                sensor.kind.to_string(),
+                format!("{:.1} {}", sensor.value, sensor.kind.unit()),
+                sensor_flag(sensor).to_string(),
+            ]
+        }),
+    )?;
+
+    let mut processes: Vec<_> = snapshot.processes.iter().collect();
+    processes.sort_by(|a, b| b.cpu_percent.total_cmp(&a.cpu_percent));
+    writeln!(out, "\nTop processes by CPU")?;
+    write_table(
+        out,
+        &["PID", "Name", "User", "CPU", "Memory"],
+        processes.into_iter().take(TOP_PROCESSES).map(|process| {
+            vec![
+                process.pid.to_string(),
+                process.name.clone(),
+                process.user.clone(),
+                format!("{:.1}%", process.cpu_percent),
+                format_size(process.memory_bytes),
+            ]
+        }),
+    )
+}
+
+fn optional<T: std::fmt::Display>(value: Option<T>, unit: &str) -> String {
+    value.map_or_else(|| "N/A".to_string(), |value| format!("{}{}", value, unit))
+}
+
+fn sensor_flag(sensor: &SensorReading) -> &'static str {
+    if sensor.is_critical() {
+        "CRITICAL"
+    } else if sensor.is_above_max() {
+        "high"
+    } else {
+        ""
+    }
+}
+
+/// Writes left-aligned columns sized to their widest cell, or "(none)" for an empty table.
+fn write_table<I>(out: &mut impl Write, headers: &[&str], rows: I) -> io::Result<()>
+where
+    I: IntoIterator<Item = Vec<String>>,
+{
+    let rows: Vec<Vec<String>> = rows.into_iter().collect();
+    if rows.is_empty() {
+        return writeln!(out, "  (none)");
+    }
+
+    let mut widths: Vec<usize> = headers
+        .iter()
+        .map(|header| header.chars().count())
+        .collect();
+    for row in &rows {
+        for (width, cell) in widths.iter_mut().zip(row) {
+            *width = (*width).max(cell.chars().count());
+        }
+    }
+
+    let header: Vec<String> = headers.iter().map(|header| header.to_string()).collect();
+    for row in std::iter::once(&header).chain(&rows) {
+        let line: Vec<String> = row
+            .iter()
+            .zip(&widths)
+            .map(|(cell, width)| format!("{:<width$}", cell, width = width))
+            .collect();
+        writeln!(out, "  {}", line.join("  ").trim_end())?;
+    }
+    Ok(())
+}
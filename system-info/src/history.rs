@@ -0,0 +1,339 @@
+use std::collections::VecDeque;
+use std::time::{Duration, SystemTime};
+
+use serde::Serialize;
+
+use crate::snapshot::CpuSnapshot;
+
+/// Number of samples kept per core for the sparklines.
+pub const CORE_HISTORY_LEN: usize = 60;
+
+/// Ten minutes of one-second samples.
+const RECENT_CAPACITY: usize = 600;
+
+/// Width of a long-term bucket; every bucket stores the mean of its samples.
+const LONG_TERM_BUCKET: Duration = Duration::from_secs(60);
+
+/// Twenty-four hours of one-minute buckets.
+const LONG_TERM_CAPACITY: usize = 24 * 60;
+
+/// A fixed-capacity queue that drops its oldest entry when full.
+#[derive(Debug, Clone)]
+pub struct RingBuffer<T> {
+    items: VecDeque<T>,
+    capacity: usize,
+}
+
+impl<T> RingBuffer<T> {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            items: VecDeque::with_capacity(capacity),
+            capacity,
+        }
+    }
+
+    pub fn push(&mut self, item: T) {
+        if self.items.len() == self.capacity {
+            self.items.pop_front();
+        }
+        self.items.push_back(item);
+    }
+
+    /// Entries from oldest to newest.
+    pub fn iter(&self) -> impl DoubleEndedIterator<Item = &T> + ExactSizeIterator {
+        self.items.iter()
+    }
+}
+
+/// Recent usage of every logical core, oldest sample first.
+#[derive(Debug, Clone, Default)]
+pub struct CoreHistory {
+    cores: Vec<RingBuffer<f32>>,
+}
+
+impl CoreHistory {
+    /// Appends the latest per-core usage, dropping the oldest sample once a core is full.
+    pub fn record(&mut self, cpu: &CpuSnapshot) {
+        // Core count only changes on hotplug; start over rather than misattribute samples
+        if self.cores.len() != cpu.cores.len() {
+            self.cores = vec![RingBuffer::new(CORE_HISTORY_LEN); cpu.cores.len()];
+        }
+
+        for (history, core) in self.cores.iter_mut().zip(&cpu.cores) {
+            history.push(core.usage_percent);
+        }
+    }
+
+    /// Usage samples for one core, oldest first.
+    pub fn core(&self, index: usize) -> impl Iterator<Item = f32> + '_ {
+        self.cores
+            .get(index)
+            .into_iter()
+            .flat_map(|history| history.iter().copied())
+    }
+}
+
+/// A single reading in a time series.
+#[derive(Debug, Clone, Copy, Serialize)]
+pub struct HistoryPoint {
+    #[serde(serialize_with = "crate::export::serialize_timestamp")]
+    pub timestamp: SystemTime,
+    pub value: f32,
+}
+
+/// Metrics that keep a time series.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Metric {
+    Cpu,
+    Ram,
+    Swap,
+    Gpu,
+    /// Bytes per second received over all non-loopback interfaces.
+    NetworkReceived,
+    /// Bytes per second transmitted over all non-loopback interfaces.
+    NetworkTransmitted,
+}
+
+impl Metric {
+    pub const ALL: [Metric; 6] = [
+        Metric::Cpu,
+        Metric::Ram,
+        Metric::Swap,
+        Metric::Gpu,
+        Metric::NetworkReceived,
+        Metric::NetworkTransmitted,
+    ];
+
+    /// Metrics expressed as percentages, charted together on the overview.
+    pub const PERCENTAGES: [Metric; 4] = [Metric::Cpu, Metric::Ram, Metric::Swap, Metric::Gpu];
+
+    /// Aggregate network throughput, charted on the network panel.
+    pub const NETWORK: [Metric; 2] = [Metric::NetworkReceived, Metric::NetworkTransmitted];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Metric::Cpu => "CPU",
+            Metric::Ram => "RAM",
+            Metric::Swap => "Swap",
+            Metric::Gpu => "GPU",
+            Metric::NetworkReceived => "Received",
+            Metric::NetworkTransmitted => "Transmitted",
+        }
+    }
+
+    /// Stable machine-readable name, used by the HTTP API.
+    pub fn key(self) -> &'static str {
+        match self {
+            Metric::Cpu => "cpu",
+            Metric::Ram => "ram",
+            Metric::Swap => "swap",
+            Metric::Gpu => "gpu",
+            Metric::NetworkReceived => "network_received",
+            Metric::NetworkTransmitted => "network_transmitted",
+        }
+    }
+
+    pub fn from_key(key: &str) -> Option<Metric> {
+        Self::ALL.into_iter().find(|metric| metric.key() == key)
+    }
+
+    pub fn is_percentage(self) -> bool {
+        Self::PERCENTAGES.contains(&self)
+    }
+}
+
+/// How far back a chart looks.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HistoryRange {
+    /// Ten minutes at full resolution.
+    Recent,
+    /// Twenty-four hours of one-minute averages.
+    LongTerm,
+}
+
+/// One metric at two resolutions: raw recent samples and downsampled long-term buckets.
+#[derive(Debug, Clone)]
+pub struct Series {
+    recent: RingBuffer<HistoryPoint>,
+    long_term: RingBuffer<HistoryPoint>,
+    /// Start, sum and count of the long-term bucket currently being filled.
+    bucket: Option<(SystemTime, f32, u32)>,
+}
+
+impl Default for Series {
+    fn default() -> Self {
+        Self {
+            recent: RingBuffer::new(RECENT_CAPACITY),
+            long_term: RingBuffer::new(LONG_TERM_CAPACITY),
+            bucket: None,
+        }
+    }
+}
+
+impl Series {
+    pub fn record(&mut self, point: HistoryPoint) {
+        self.recent.push(point);
+
+        let (start, sum, count) = self.bucket.get_or_insert((point.timestamp, 0.0, 0));
+        let elapsed = point
+            .timestamp
+            .duration_since(*start)
+            .unwrap_or(Duration::ZERO);
+
+        if elapsed >= LONG_TERM_BUCKET && *count > 0 {
+            // Close the full bucket and start a new one with this sample
+            self.long_term.push(HistoryPoint {
+                timestamp: *start,
+                value: *sum / *count as f32,
+            });
+            self.bucket = Some((point.timestamp, point.value, 1));
+        } else {
+            *sum += point.value;
+            *count += 1;
+        }
+    }
+
+    /// Points for the requested range, oldest first.
+    pub fn points(&self, range: HistoryRange) -> &RingBuffer<HistoryPoint> {
+        match range {
+            HistoryRange::Recent => &self.recent,
+            HistoryRange::LongTerm => &self.long_term,
+        }
+    }
+}
+
+/// Time series for every [`Metric`].
+#[derive(Debug, Clone, Default)]
+pub struct MetricsHistory {
+    cpu: Series,
+    ram: Series,
+    swap: Series,
+    gpu: Series,
+    network_received: Series,
+    network_transmitted: Series,
+}
+
+impl MetricsHistory {
+    pub fn record(&mut self, metric: Metric, timestamp: SystemTime, value: f32) {
+        self.series_mut(metric)
+            .record(HistoryPoint { timestamp, value });
+    }
+
+    pub fn series(&self, metric: Metric) -> &Series {
+        match metric {
+            Metric::Cpu => &self.cpu,
+            Metric::Ram => &self.ram,
+            Metric::Swap => &self.swap,
+            Metric::Gpu => &self.gpu,
+            Metric::NetworkReceived => &self.network_received,
+            Metric::NetworkTransmitted => &self.network_transmitted,
+        }
+    }
+
+    fn series_mut(&mut self, metric: Metric) -> &mut Series {
+        match metric {
+            Metric::Cpu => &mut self.cpu,
+            Metric::Ram => &mut self.ram,
+            Metric::Swap => &mut self.swap,
+            Metric::Gpu => &mut self.gpu,
+            Metric::NetworkReceived => &mut self.network_received,
+            Metric::NetworkTransmitted => &mut self.network_transmitted,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn at(start: SystemTime, secs: u64) -> SystemTime {
+        start + Duration::from_secs(secs)
+    }
+
+    fn long_term(series: &Series, start: SystemTime) -> Vec<(u64, f32)> {
+        series
+            .points(HistoryRange::LongTerm)
+            .iter()
+            .map(|point| {
+                let offset = point.timestamp.duration_since(start).unwrap();
+                (offset.as_secs(), point.value)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn ring_buffer_drops_the_oldest_entry_at_capacity() {
+        let mut buffer = RingBuffer::new(3);
+        for value in 1..=5 {
+            buffer.push(value);
+        }
+        assert_eq!(buffer.iter().copied().collect::<Vec<_>>(), [3, 4, 5]);
+        assert_eq!(buffer.iter().len(), 3);
+    }
+
+    #[test]
+    fn long_term_buckets_hold_the_mean_of_each_minute() {
+        let start = SystemTime::UNIX_EPOCH + Duration::from_secs(1_700_000_000);
+        let mut series = Series::default();
+        // Six samples in the first minute, then two in the second
+        for (secs, value) in [
+            (0, 10.0),
+            (10, 20.0),
+            (20, 30.0),
+            (30, 40.0),
+            (40, 50.0),
+            (50, 60.0),
+        ] {
+            series.record(HistoryPoint {
+                timestamp: at(start, secs),
+                value,
+            });
+        }
+        for (secs, value) in [(60, 5.0), (90, 15.0), (120, 0.0)] {
+            series.record(HistoryPoint {
+                timestamp: at(start, secs),
+                value,
+            });
+        }
+
+        assert_eq!(long_term(&series, start), [(0, 35.0), (60, 10.0)]);
+        assert_eq!(series.points(HistoryRange::Recent).iter().len(), 9);
+    }
+
+    #[test]
+    fn a_gap_in_the_samples_leaves_no_bucket_behind() {
+        let start = SystemTime::UNIX_EPOCH + Duration::from_secs(1_700_000_000);
+        let mut series = Series::default();
+        // Nothing was recorded between 0:40 and 3:20, e.g. while the machine slept
+        for (secs, value) in [(0, 10.0), (40, 30.0), (200, 50.0), (230, 70.0), (260, 0.0)] {
+            series.record(HistoryPoint {
+                timestamp: at(start, secs),
+                value,
+            });
+        }
+
+        assert_eq!(long_term(&series, start), [(0, 20.0), (200, 60.0)]);
+    }
+
+    #[test]
+    fn long_term_history_keeps_twenty_four_hours() {
+        let start = SystemTime::UNIX_EPOCH + Duration::from_secs(1_700_000_000);
+        let mut series = Series::default();
+        // One sample a minute closes one bucket a minute
+        let minutes = LONG_TERM_CAPACITY as u64 + 2;
+        for minute in 0..minutes {
+            series.record(HistoryPoint {
+                timestamp: at(start, minute * 60),
+                value: minute as f32,
+            });
+        }
+
+        let points = long_term(&series, start);
+        assert_eq!(points.len(), LONG_TERM_CAPACITY);
+        assert_eq!(points[0], (60, 1.0));
+        assert_eq!(
+            points[points.len() - 1],
+            ((minutes - 2) * 60, (minutes - 2) as f32)
+        );
+    }
+}
@@ -0,0 +1,381 @@
+#![cfg_attr(not(debug_assertions), windows_subsystem = "windows")]
+
+use std::collections::HashSet;
+use std::error::Error;
+use std::sync::{Arc, Mutex};
+use std::time::Instant;
+
+use clap::Parser;
+use slint::{ModelRc, SharedString, VecModel};
+use tokio::sync::oneshot;
+use tokio::{task, time};
+
+mod audit;
+mod cli;
+mod collector;
+mod csv_log;
+mod export;
+mod format;
+mod headless;
+mod history;
+mod metrics;
+mod monitor;
+mod process_actions;
+mod process_tree;
+mod recorder;
+mod replay;
+mod server;
+mod sinks;
+mod snapshot;
+mod tui;
+mod ui;
+
+use audit::AuditLog;
+use cli::Cli;
+use collector::CollectorRegistry;
+use csv_log::CsvLog;
+use monitor::Monitor;
+use process_actions::ProcessAction;
+use process_tree::ProcessTree;
+use recorder::Recorder;
+
+slint::include_modules!();
+
+/// Redraws the UI from the latest snapshot. Collection stays with the background poller,
+/// which keeps each collector on its own interval.
+fn refresh_system_info(ui: &AppWindow, monitor: &Mutex<Monitor>) {
+    if let Ok(monitor) = monitor.lock() {
+        ui::render(ui, &monitor.view());
+    }
+}
+
+/// Applies a confirmed process action, records it and reports the outcome in the UI.
+fn handle_process_action(
+    ui: &AppWindow,
+    monitor: &Mutex<Monitor>,
+    audit: &Mutex<AuditLog>,
+    pid: &str,
+    action: &str,
+    nice: i32,
+) {
+    if action == "kill-tree" {
+        if let Ok(pid) = pid.parse::<u32>() {
+            kill_process_tree(ui, monitor, audit, pid);
+        }
+        return;
+    }
+
+    let (Ok(pid), Some(action)) = (pid.parse::<u32>(), ProcessAction::parse(action, nice)) else {
+        log::warn!(
+            "Ignoring malformed process action '{}' for '{}'",
+            action,
+            pid
+        );
+        return;
+    };
+
+    let name = monitor
+        .lock()
+        .ok()
+        .and_then(|monitor| {
+            let snapshot = monitor.snapshot();
+            let process = snapshot
+                .processes
+                .iter()
+                .find(|process| process.pid == pid)?;
+            Some(process.name.clone())
+        })
+        .unwrap_or_default();
+
+    let outcome = process_actions::perform(pid, action);
+    let status = match &outcome {
+        Ok(()) => format!("Sent {} to '{}' (PID {})", action, name, pid),
+        Err(err) => format!("Could not {} '{}' (PID {}): {}", action, name, pid, err),
+    };
+    ui.set_process_action_status(status.into());
+
+    if let Ok(mut audit) = audit.lock() {
+        audit.record(action, pid, &name, &outcome);
+        show_audit_entries(ui, &audit);
+    }
+}
+
+/// Kills a process and every descendant, auditing each one.
+///
+/// The whole subtree is stopped before anything is killed: a supervising parent that saw
+/// a child die would otherwise fork a replacement we do not know about. A tree that holds
+/// this monitor, such as that of the terminal it was started from, is refused: stopping
+/// ourselves would leave everything else stopped for good.
+fn kill_process_tree(ui: &AppWindow, monitor: &Mutex<Monitor>, audit: &Mutex<AuditLog>, pid: u32) {
+    let targets: Vec<(u32, String)> = match monitor.lock() {
+        Ok(monitor) => {
+            let processes = &monitor.snapshot().processes;
+            let tree = ProcessTree::build(processes);
+            tree.subtree(pid)
+                .into_iter()
+                .map(|target| {
+                    let name = processes
+                        .iter()
+                        .find(|process| process.pid == target)
+                        .map(|process| process.name.clone())
+                        .unwrap_or_default();
+                    (target, name)
+                })
+                .collect()
+        }
+        Err(_) => return,
+    };
+
+    let Some((_, root_name)) = targets.first().cloned() else {
+        ui.set_process_action_status(format!("Process {} no longer exists", pid).into());
+        return;
+    };
+    if targets
+        .iter()
+        .any(|(target, _)| *target == std::process::id())
+    {
+        ui.set_process_action_status(
+            format!(
+                "Refusing to kill the tree of '{}' (PID {}): it contains this monitor",
+                root_name, pid
+            )
+            .into(),
+        );
+        return;
+    }
+
+    let Ok(mut audit) = audit.lock() else {
+        return;
+    };
+    let mut stopped = HashSet::new();
+    for (target, name) in &targets {
+        let outcome = process_actions::perform(*target, ProcessAction::Suspend);
+        match outcome {
+            Ok(()) => {
+                stopped.insert(*target);
+            }
+            // Still try to kill it, but leave a record of why the tree was not frozen
+            Err(_) => audit.record(ProcessAction::Suspend, *target, name, &outcome),
+        }
+    }
+    let mut killed = 0;
+    for (target, name) in &targets {
+        let outcome = process_actions::perform(*target, ProcessAction::Kill);
+        killed += usize::from(outcome.is_ok());
+        audit.record(ProcessAction::Kill, *target, name, &outcome);
+
+        // A survivor must not stay frozen by the stop above
+        if outcome.is_err() && stopped.contains(target) {
+            let resumed = process_actions::perform(*target, ProcessAction::Resume);
+            audit.record(ProcessAction::Resume, *target, name, &resumed);
+        }
+    }
+
+    ui.set_process_action_status(
+        format!(
+            "Killed {} of {} processes in the tree of '{}' (PID {})",
+            killed,
+            targets.len(),
+            root_name,
+            pid
+        )
+        .into(),
+    );
+    show_audit_entries(ui, &audit);
+}
+
+fn show_audit_entries(ui: &AppWindow, audit: &AuditLog) {
+    let entries: Vec<SharedString> = audit.recent().map(|entry| entry.into()).collect();
+    ui.set_audit_entries(ModelRc::new(VecModel::from(entries)));
+}
+
+#[tokio::main] // Use Tokio runtime for async support
+async fn main() -> Result<(), Box<dyn Error>> {
+    env_logger::init();
+    let cli = Cli::parse();
+
+    // Open the recording and sinks up front so a bad path fails before anything starts
+    let recorder = match &cli.record {
+        Some(path) => Some(Recorder::open(path, cli.retention())?),
+        None => None,
+    };
+    let sinks = match &cli.sinks {
+        Some(path) => sinks::load(path)?,
+        None => Vec::new(),
+    };
+
+    if let Some(addr) = cli.serve_metrics.or(cli.web) {
+        let monitor = Arc::new(Mutex::new(Monitor::new(CollectorRegistry::with_defaults())));
+        if let Some(recorder) = recorder {
+            recorder::spawn(recorder, Arc::clone(&monitor));
+        }
+        sinks::spawn(sinks, Arc::clone(&monitor));
+        server::run(addr, monitor, cli.web.is_some()).await?;
+        return Ok(());
+    }
+    if let Some(path) = &cli.log_csv {
+        let log = CsvLog::open(path, cli.rotate)?;
+        let monitor = Arc::new(Mutex::new(Monitor::new(CollectorRegistry::with_defaults())));
+        if let Some(recorder) = recorder {
+            recorder::spawn(recorder, Arc::clone(&monitor));
+        }
+        sinks::spawn(sinks, Arc::clone(&monitor));
+        return csv_log::run(log, monitor, cli.interval).await;
+    }
+    if cli.tui {
+        return tui::run();
+    }
+    if let Some(path) = &cli.replay {
+        return replay::run(path);
+    }
+    if cli.once || cli.format.is_some() {
+        return headless::run(cli.format).await;
+    }
+
+    // Initialize the UI and the metric collectors
+    let ui = AppWindow::new()?;
+    let monitor = Arc::new(Mutex::new(Monitor::new(CollectorRegistry::with_defaults())));
+    let audit = Arc::new(Mutex::new(AuditLog::new()));
+
+    if let Some(recorder) = recorder {
+        recorder::spawn(recorder, Arc::clone(&monitor));
+    }
+    sinks::spawn(sinks, Arc::clone(&monitor));
+
+    // Let local tools query the same monitor the window shows, until the window closes
+    let (close_api, api_closed) = oneshot::channel::<()>();
+    if let Some(addr) = cli.api {
+        let monitor = Arc::clone(&monitor);
+        let shutdown = async move {
+            let _ = api_closed.await;
+        };
+        tokio::spawn(async move {
+            if let Err(err) = server::serve(addr, monitor, false, shutdown).await {
+                log::error!("HTTP API on {} stopped: {}", addr, err);
+            }
+        });
+    }
+
+    // Periodically run whichever collectors are due
+    {
+        let ui_handle = ui.as_weak();
+        let monitor = Arc::clone(&monitor);
+
+        tokio::spawn(async move {
+            loop {
+                // Collectors block on procfs, sysfs and NVML; keep them off the runtime workers
+                let round = task::spawn_blocking({
+                    let monitor = Arc::clone(&monitor);
+                    move || {
+                        let mut monitor = monitor.lock().ok()?;
+                        let now = Instant::now();
+                        let collected = monitor.poll(now).then(|| monitor.view());
+                        Some((collected, monitor.next_due(now)))
+                    }
+                });
+                let Ok(Some((collected, wait))) = round.await else {
+                    break;
+                };
+
+                if let Some(view) = collected {
+                    log::debug!("Collected snapshot at {:?}", view.snapshot.timestamp);
+                    // The window lives on the UI thread, so hand the view over to its event loop
+                    let rendered =
+                        ui_handle.upgrade_in_event_loop(move |ui| ui::render(&ui, &view));
+                    if rendered.is_err() {
+                        break; // Event loop has shut down
+                    }
+                }
+
+                time::sleep(wait).await;
+            }
+        });
+    }
+
+    // UI logic for button click handling (if applicable)
+    ui.on_request_increase_value({
+        let ui_handle = ui.as_weak();
+        let monitor = Arc::clone(&monitor);
+
+        move || {
+            if let Some(ui) = ui_handle.upgrade() {
+                refresh_system_info(&ui, &monitor);
+            }
+        }
+    });
+
+    // Re-sort and re-filter the process table as soon as its controls change
+    ui.on_process_view_changed({
+        let ui_handle = ui.as_weak();
+        let monitor = Arc::clone(&monitor);
+
+        move || {
+            if let (Some(ui), Ok(monitor)) = (ui_handle.upgrade(), monitor.lock()) {
+                ui::render_processes(&ui, &monitor.snapshot().processes);
+            }
+        }
+    });
+
+    // Expand or collapse a branch of the process tree
+    ui.on_process_tree_toggled({
+        let ui_handle = ui.as_weak();
+        let monitor = Arc::clone(&monitor);
+
+        move |pid| {
+            ui::toggle_tree_node(pid as u32);
+            if let (Some(ui), Ok(monitor)) = (ui_handle.upgrade(), monitor.lock()) {
+                ui::render_processes(&ui, &monitor.snapshot().processes);
+            }
+        }
+    });
+
+    // Signal or renice a process once the user has confirmed it
+    ui.on_process_action({
+        let ui_handle = ui.as_weak();
+        let monitor = Arc::clone(&monitor);
+        let audit = Arc::clone(&audit);
+
+        move |pid, action, nice| {
+            if let Some(ui) = ui_handle.upgrade() {
+                handle_process_action(&ui, &monitor, &audit, &pid, &action, nice);
+            }
+        }
+    });
+
+    // Serialize the current snapshot for the "Copy as JSON" button
+    ui.on_snapshot_json({
+        let monitor = Arc::clone(&monitor);
+
+        move || {
+            let Ok(monitor) = monitor.lock() else {
+                return SharedString::new();
+            };
+            match export::to_string(monitor.snapshot(), export::Format::Json) {
+                Ok(json) => json.into(),
+                Err(err) => {
+                    log::error!("{}", err);
+                    SharedString::new()
+                }
+            }
+        }
+    });
+
+    // Let the user switch individual collectors on and off
+    ui.on_collector_toggled({
+        let monitor = Arc::clone(&monitor);
+
+        move |name, enabled| {
+            if let Ok(mut monitor) = monitor.lock() {
+                if !monitor.registry_mut().set_enabled(&name, enabled) {
+                    log::warn!("Unknown collector '{}'", name);
+                }
+            }
+        }
+    });
+
+    // Run the UI
+    let result = ui.run();
+    let _ = close_api.send(());
+    result?;
+    Ok(())
+}
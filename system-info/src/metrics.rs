@@ -0,0 +1,507 @@
+use std::fmt::Write;
+
+use crate::snapshot::{
+    BlockDeviceSnapshot, GpuSnapshot, InterfaceSnapshot, MountSnapshot, SensorKind, SystemSnapshot,
+};
+
+/// Every exported metric name starts with this.
+const PREFIX: &str = "system_info";
+
+/// The two text exposition formats that scrapers negotiate between.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Flavor {
+    /// Prometheus text format 0.0.4.
+    Prometheus,
+    /// OpenMetrics 1.0.0 text format.
+    OpenMetrics,
+}
+
+impl Flavor {
+    /// Picks OpenMetrics when the scraper's `Accept` header asks for it.
+    pub fn from_accept(accept: Option<&str>) -> Self {
+        match accept {
+            Some(accept) if accept.contains("application/openmetrics-text") => Flavor::OpenMetrics,
+            _ => Flavor::Prometheus,
+        }
+    }
+
+    pub fn content_type(self) -> &'static str {
+        match self {
+            Flavor::Prometheus => "text/plain; version=0.0.4; charset=utf-8",
+            Flavor::OpenMetrics => "application/openmetrics-text; version=1.0.0; charset=utf-8",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Kind {
+    Gauge,
+    Counter,
+}
+
+type Labels = Vec<(&'static str, String)>;
+
+/// Builds an exposition one metric family at a time.
+struct Exposition {
+    flavor: Flavor,
+    text: String,
+}
+
+impl Exposition {
+    /// Writes one family. Counter names passed here carry no `_total` suffix; it is
+    /// added to the samples, and to the `TYPE` line too for the Prometheus format.
+    fn family(
+        &mut self,
+        name: &str,
+        kind: Kind,
+        help: &str,
+        samples: impl IntoIterator<Item = (Labels, f64)>,
+    ) {
+        let samples: Vec<_> = samples.into_iter().collect();
+        if samples.is_empty() {
+            return;
+        }
+
+        let name = format!("{}_{}", PREFIX, name);
+        let sample_name = match kind {
+            Kind::Gauge => name.clone(),
+            Kind::Counter => format!("{}_total", name),
+        };
+        let (type_name, kind_name) = match (kind, self.flavor) {
+            (Kind::Gauge, _) => (&name, "gauge"),
+            (Kind::Counter, Flavor::OpenMetrics) => (&name, "counter"),
+            (Kind::Counter, Flavor::Prometheus) => (&sample_name, "counter"),
+        };
+
+        let _ = writeln!(self.text, "# HELP {} {}", type_name, help);
+        let _ = writeln!(self.text, "# TYPE {} {}", type_name, kind_name);
+        for (labels, value) in samples {
+            self.text.push_str(&sample_name);
+            if !labels.is_empty() {
+                let labels: Vec<String> = labels
+                    .iter()
+                    .map(|(key, value)| format!("{}=\"{}\"", key, escape(value)))
+                    .collect();
+                let _ = write!(self.text, "{{{}}}", labels.join(","));
+            }
+            let _ = writeln!(self.text, " {}", number(value));
+        }
+    }
+
+    fn finish(mut self) -> String {
+        if self.flavor == Flavor::OpenMetrics {
+            self.text.push_str("# EOF\n");
+        }
+        self.text
+    }
+}
+
+/// Escapes a label value as both text formats require.
+fn escape(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
+
+/// Formats a sample value. Rust prints infinities as `inf`, which OpenMetrics rejects;
+/// both formats accept `NaN`, `+Inf` and `-Inf`.
+fn number(value: f64) -> String {
+    if value.is_nan() {
+        "NaN".to_string()
+    } else if value == f64::INFINITY {
+        "+Inf".to_string()
+    } else if value == f64::NEG_INFINITY {
+        "-Inf".to_string()
+    } else {
+        value.to_string()
+    }
+}
+
+/// Writes a gauge with a single unlabelled sample.
+fn single(out: &mut Exposition, name: &str, help: &str, value: f64) {
+    out.family(name, Kind::Gauge, help, [(Vec::new(), value)]);
+}
+
+/// Writes a gauge with one sample per GPU that reports the value. GPUs are labelled
+/// by position so that two identical cards stay distinct.
+fn per_gpu(
+    out: &mut Exposition,
+    name: &str,
+    help: &str,
+    gpus: &[GpuSnapshot],
+    value: impl Fn(&GpuSnapshot) -> Option<f64>,
+) {
+    let samples = gpus
+        .iter()
+        .enumerate()
+        .filter_map(|(index, gpu)| Some((vec![("gpu", index.to_string())], value(gpu)?)));
+    out.family(name, Kind::Gauge, help, samples);
+}
+
+/// Writes a counter with one sample per block device.
+fn per_device(
+    out: &mut Exposition,
+    name: &str,
+    help: &str,
+    devices: &[BlockDeviceSnapshot],
+    value: impl Fn(&BlockDeviceSnapshot) -> u64,
+) {
+    let samples = devices
+        .iter()
+        .map(|device| (vec![("device", device.name.clone())], value(device) as f64));
+    out.family(name, Kind::Counter, help, samples);
+}
+
+/// Writes a counter with one sample per interface that reports the value.
+fn per_interface(
+    out: &mut Exposition,
+    name: &str,
+    help: &str,
+    interfaces: &[InterfaceSnapshot],
+    value: impl Fn(&InterfaceSnapshot) -> Option<u64>,
+) {
+    let samples = interfaces.iter().filter_map(|interface| {
+        Some((
+            vec![("interface", interface.name.clone())],
+            value(interface)? as f64,
+        ))
+    });
+    out.family(name, Kind::Counter, help, samples);
+}
+
+/// Renders the snapshot as gauges and counters. Percentages are exported as 0-1
+/// ratios and clocks in hertz, following Prometheus naming conventions.
+pub fn render(snapshot: &SystemSnapshot, flavor: Flavor) -> String {
+    let mut out = Exposition {
+        flavor,
+        text: String::new(),
+    };
+
+    // CPU
+    let cpu = &snapshot.cpu;
+    single(
+        &mut out,
+        "cpu_usage_ratio",
+        "Average CPU usage across all logical cores.",
+        f64::from(cpu.usage_percent) / 100.0,
+    );
+    out.family(
+        "cpu_core_usage_ratio",
+        Kind::Gauge,
+        "CPU usage of one logical core.",
+        cpu.cores.iter().map(|core| {
+            (
+                vec![("core", core.name.clone())],
+                f64::from(core.usage_percent) / 100.0,
+            )
+        }),
+    );
+    out.family(
+        "cpu_core_frequency_hertz",
+        Kind::Gauge,
+        "Current clock of one logical core.",
+        cpu.cores.iter().map(|core| {
+            (
+                vec![("core", core.name.clone())],
+                core.frequency_mhz as f64 * 1e6,
+            )
+        }),
+    );
+
+    // Memory
+    let memory = &snapshot.memory;
+    single(
+        &mut out,
+        "memory_total_bytes",
+        "Physical memory.",
+        memory.total_bytes as f64,
+    );
+    single(
+        &mut out,
+        "memory_used_bytes",
+        "Physical memory in use.",
+        memory.used_bytes as f64,
+    );
+    single(
+        &mut out,
+        "memory_free_bytes",
+        "Physical memory not used for anything, not even cache.",
+        memory.free_bytes as f64,
+    );
+    if let Some(breakdown) = &memory.breakdown {
+        single(
+            &mut out,
+            "memory_available_bytes",
+            "Memory available to applications without swapping.",
+            breakdown.available_bytes as f64,
+        );
+        single(
+            &mut out,
+            "memory_cached_bytes",
+            "Page cache, including shared memory.",
+            breakdown.cached_bytes as f64,
+        );
+        single(
+            &mut out,
+            "memory_buffers_bytes",
+            "Block device buffers.",
+            breakdown.buffers_bytes as f64,
+        );
+    }
+    single(
+        &mut out,
+        "swap_total_bytes",
+        "Swap space.",
+        memory.swap_total_bytes as f64,
+    );
+    single(
+        &mut out,
+        "swap_used_bytes",
+        "Swap space in use.",
+        memory.swap_used_bytes as f64,
+    );
+
+    // GPUs; telemetry only exists where NVML reports it
+    let gpus = &snapshot.gpus;
+    out.family(
+        "gpu_info",
+        Kind::Gauge,
+        "Static information about a graphics adapter; always 1.",
+        gpus.iter().enumerate().map(|(index, gpu)| {
+            let labels = vec![
+                ("gpu", index.to_string()),
+                ("name", gpu.name.clone()),
+                ("type", gpu.device_type.to_string().to_lowercase()),
+                ("driver", gpu.driver.clone()),
+                ("driver_version", gpu.driver_version.clone()),
+            ];
+            (labels, 1.0)
+        }),
+    );
+    per_gpu(
+        &mut out,
+        "gpu_memory_total_bytes",
+        "Dedicated video memory.",
+        gpus,
+        |gpu| Some(gpu.vram.as_ref()?.total_bytes as f64),
+    );
+    per_gpu(
+        &mut out,
+        "gpu_memory_used_bytes",
+        "Dedicated video memory in use.",
+        gpus,
+        |gpu| Some(gpu.vram.as_ref()?.used_bytes? as f64),
+    );
+    per_gpu(
+        &mut out,
+        "gpu_utilization_ratio",
+        "Share of time the GPU was busy.",
+        gpus,
+        |gpu| Some(f64::from(gpu.telemetry.as_ref()?.utilization_percent?) / 100.0),
+    );
+    per_gpu(
+        &mut out,
+        "gpu_memory_utilization_ratio",
+        "Share of time video memory was being read or written.",
+        gpus,
+        |gpu| Some(f64::from(gpu.telemetry.as_ref()?.memory_utilization_percent?) / 100.0),
+    );
+    per_gpu(
+        &mut out,
+        "gpu_graphics_clock_hertz",
+        "Graphics clock.",
+        gpus,
+        |gpu| Some(f64::from(gpu.telemetry.as_ref()?.graphics_clock_mhz?) * 1e6),
+    );
+    per_gpu(
+        &mut out,
+        "gpu_memory_clock_hertz",
+        "Memory clock.",
+        gpus,
+        |gpu| Some(f64::from(gpu.telemetry.as_ref()?.memory_clock_mhz?) * 1e6),
+    );
+    per_gpu(
+        &mut out,
+        "gpu_temperature_celsius",
+        "Core temperature.",
+        gpus,
+        |gpu| Some(f64::from(gpu.telemetry.as_ref()?.temperature_celsius?)),
+    );
+    per_gpu(
+        &mut out,
+        "gpu_fan_speed_ratio",
+        "Fan speed relative to its maximum.",
+        gpus,
+        |gpu| Some(f64::from(gpu.telemetry.as_ref()?.fan_speed_percent?) / 100.0),
+    );
+    per_gpu(&mut out, "gpu_power_watts", "Power draw.", gpus, |gpu| {
+        Some(f64::from(gpu.telemetry.as_ref()?.power_draw_milliwatts?) / 1000.0)
+    });
+
+    // Filesystems
+    let mount_labels = |mount: &MountSnapshot| {
+        vec![
+            ("mountpoint", mount.mount_point.clone()),
+            ("device", mount.device.clone()),
+            ("fstype", mount.file_system.clone()),
+        ]
+    };
+    out.family(
+        "filesystem_size_bytes",
+        Kind::Gauge,
+        "Filesystem size.",
+        snapshot
+            .storage
+            .mounts
+            .iter()
+            .map(|mount| (mount_labels(mount), mount.total_bytes as f64)),
+    );
+    out.family(
+        "filesystem_avail_bytes",
+        Kind::Gauge,
+        "Filesystem space available to unprivileged users.",
+        snapshot
+            .storage
+            .mounts
+            .iter()
+            .map(|mount| (mount_labels(mount), mount.available_bytes as f64)),
+    );
+
+    // Block devices
+    let devices = &snapshot.storage.devices;
+    per_device(
+        &mut out,
+        "disk_read_bytes",
+        "Bytes read from a block device.",
+        devices,
+        |device| device.total_read_bytes,
+    );
+    per_device(
+        &mut out,
+        "disk_written_bytes",
+        "Bytes written to a block device.",
+        devices,
+        |device| device.total_written_bytes,
+    );
+    per_device(
+        &mut out,
+        "disk_reads_completed",
+        "Read requests completed by a block device.",
+        devices,
+        |device| device.total_reads,
+    );
+    per_device(
+        &mut out,
+        "disk_writes_completed",
+        "Write requests completed by a block device.",
+        devices,
+        |device| device.total_writes,
+    );
+
+    // Network interfaces
+    let interfaces = &snapshot.networks;
+    per_interface(
+        &mut out,
+        "network_receive_bytes",
+        "Bytes received on an interface.",
+        interfaces,
+        |interface| Some(interface.total_received_bytes),
+    );
+    per_interface(
+        &mut out,
+        "network_transmit_bytes",
+        "Bytes transmitted on an interface.",
+        interfaces,
+        |interface| Some(interface.total_transmitted_bytes),
+    );
+    per_interface(
+        &mut out,
+        "network_receive_errors",
+        "Receive errors on an interface.",
+        interfaces,
+        |interface| Some(interface.receive_errors),
+    );
+    per_interface(
+        &mut out,
+        "network_transmit_errors",
+        "Transmit errors on an interface.",
+        interfaces,
+        |interface| Some(interface.transmit_errors),
+    );
+    per_interface(
+        &mut out,
+        "network_receive_drops",
+        "Incoming packets dropped on an interface.",
+        interfaces,
+        |interface| interface.receive_drops,
+    );
+    per_interface(
+        &mut out,
+        "network_transmit_drops",
+        "Outgoing packets dropped on an interface.",
+        interfaces,
+        |interface| interface.transmit_drops,
+    );
+
+    // Hardware sensors
+    for (kind, name, help) in [
+        (
+            SensorKind::Temperature,
+            "sensor_temperature_celsius",
+            "Temperature sensor reading.",
+        ),
+        (SensorKind::Fan, "sensor_fan_rpm", "Fan speed."),
+        (
+            SensorKind::Voltage,
+            "sensor_voltage_volts",
+            "Voltage sensor reading.",
+        ),
+        (
+            SensorKind::Power,
+            "sensor_power_watts",
+            "Power sensor reading.",
+        ),
+    ] {
+        out.family(
+            name,
+            Kind::Gauge,
+            help,
+            snapshot
+                .sensors
+                .iter()
+                .filter(|sensor| sensor.kind == kind)
+                .map(|sensor| (vec![("sensor", sensor.label.clone())], sensor.value)),
+        );
+    }
+
+    out.finish()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn non_finite_values_use_the_exposition_spelling() {
+        let mut out = Exposition {
+            flavor: Flavor::OpenMetrics,
+            text: String::new(),
+        };
+        for (name, value) in [
+            ("nan", f64::NAN),
+            ("up", f64::INFINITY),
+            ("down", f64::NEG_INFINITY),
+            ("plain", 1.5),
+        ] {
+            single(&mut out, name, "Test value.", value);
+        }
+        let text = out.finish();
+
+        assert!(text.contains("\nsystem_info_nan NaN\n"));
+        assert!(text.contains("\nsystem_info_up +Inf\n"));
+        assert!(text.contains("\nsystem_info_down -Inf\n"));
+        assert!(text.contains("\nsystem_info_plain 1.5\n"));
+        assert!(text.ends_with("# EOF\n"));
+    }
+}
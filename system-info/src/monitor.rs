@@ -0,0 +1,146 @@
+use std::time::{Duration, Instant, SystemTime};
+
+use tokio::sync::{mpsc, watch};
+
+use crate::collector::{CollectorRegistry, Sample};
+use crate::history::{CoreHistory, Metric, MetricsHistory};
+use crate::snapshot::{network_throughput, SystemSnapshot};
+
+/// Owns the collectors together with the latest snapshot and everything derived from it.
+pub struct Monitor {
+    registry: CollectorRegistry,
+    snapshot: SystemSnapshot,
+    core_history: CoreHistory,
+    history: MetricsHistory,
+    /// Timestamp of the latest snapshot, for tasks that follow the collectors live.
+    updates: watch::Sender<SystemTime>,
+    /// Receivers of every poll's samples, for tasks that must not miss or repeat one.
+    taps: Vec<mpsc::UnboundedSender<(SystemTime, Vec<Sample>)>>,
+}
+
+/// An owned copy of the monitor state, cheap enough to hand to the UI thread every tick.
+#[derive(Debug, Clone)]
+pub struct MonitorView {
+    pub snapshot: SystemSnapshot,
+    pub core_history: CoreHistory,
+    pub history: MetricsHistory,
+}
+
+impl Monitor {
+    pub fn new(registry: CollectorRegistry) -> Self {
+        let (updates, _) = watch::channel(SystemTime::UNIX_EPOCH);
+        Self {
+            registry,
+            snapshot: SystemSnapshot::new(),
+            core_history: CoreHistory::default(),
+            history: MetricsHistory::default(),
+            updates,
+            taps: Vec::new(),
+        }
+    }
+
+    /// Runs the collectors that are due. Returns `true` if anything was collected.
+    pub fn poll(&mut self, now: Instant) -> bool {
+        let samples = self.registry.poll(now);
+        self.ingest(samples)
+    }
+
+    /// Runs every enabled collector immediately.
+    pub fn collect_all(&mut self) -> bool {
+        let samples = self.registry.collect_all();
+        self.ingest(samples)
+    }
+
+    /// Time until the next collector becomes due.
+    pub fn next_due(&self, now: Instant) -> Duration {
+        self.registry.next_due(now)
+    }
+
+    pub fn snapshot(&self) -> &SystemSnapshot {
+        &self.snapshot
+    }
+
+    pub fn history(&self) -> &MetricsHistory {
+        &self.history
+    }
+
+    /// Notifies the receiver every time a new snapshot has been collected.
+    pub fn subscribe(&self) -> watch::Receiver<SystemTime> {
+        self.updates.subscribe()
+    }
+
+    /// Hands over the samples of every later poll, together with their timestamp. Unlike
+    /// [`Monitor::subscribe`] nothing is skipped, and sections that were not collected
+    /// are not repeated.
+    pub fn tap(&mut self) -> mpsc::UnboundedReceiver<(SystemTime, Vec<Sample>)> {
+        let (sender, receiver) = mpsc::unbounded_channel();
+        self.taps.push(sender);
+        receiver
+    }
+
+    pub fn registry_mut(&mut self) -> &mut CollectorRegistry {
+        &mut self.registry
+    }
+
+    pub fn view(&self) -> MonitorView {
+        MonitorView {
+            snapshot: self.snapshot.clone(),
+            core_history: self.core_history.clone(),
+            history: self.history.clone(),
+        }
+    }
+
+    /// Applies samples taken at `now` rather than the current time, e.g. frames of a recording.
+    pub fn ingest_at(&mut self, samples: Vec<Sample>, now: SystemTime) -> bool {
+        if samples.is_empty() {
+            return false;
+        }
+
+        self.taps
+            .retain(|tap| tap.send((now, samples.clone())).is_ok());
+        for sample in samples {
+            self.record_history(&sample, now);
+            self.snapshot.apply(sample);
+        }
+        self.snapshot.timestamp = now;
+        self.updates.send_replace(now);
+        true
+    }
+
+    fn ingest(&mut self, samples: Vec<Sample>) -> bool {
+        self.ingest_at(samples, SystemTime::now())
+    }
+
+    fn record_history(&mut self, sample: &Sample, now: SystemTime) {
+        match sample {
+            Sample::Cpu(cpu) => {
+                self.core_history.record(cpu);
+                self.history.record(Metric::Cpu, now, cpu.usage_percent);
+            }
+            Sample::Memory(memory) => {
+                self.history.record(Metric::Ram, now, memory.used_percent());
+                if let Some(swap) = memory.swap_used_percent() {
+                    self.history.record(Metric::Swap, now, swap);
+                }
+            }
+            Sample::Gpu(gpus) => {
+                // Only vendor telemetry reports load; chart the first GPU that has it
+                let utilization = gpus
+                    .iter()
+                    .filter_map(|gpu| gpu.telemetry.as_ref()?.utilization_percent)
+                    .next();
+                if let Some(utilization) = utilization {
+                    self.history.record(Metric::Gpu, now, utilization as f32);
+                }
+            }
+            Sample::Network(interfaces) => {
+                let (received, transmitted) = network_throughput(interfaces);
+                self.history
+                    .record(Metric::NetworkReceived, now, received as f32);
+                self.history
+                    .record(Metric::NetworkTransmitted, now, transmitted as f32);
+            }
+            Sample::Processes(_) | Sample::Storage(_) | Sample::Sensors(_) => {}
+        }
+    }
+}
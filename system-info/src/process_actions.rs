@@ -0,0 +1,171 @@
+use std::error::Error;
+use std::fmt;
+use std::io;
+
+use sysinfo::{Pid, PidExt, ProcessExt, ProcessRefreshKind, Signal, System, SystemExt};
+
+/// Something the user can do to a running process.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ProcessAction {
+    /// Ask the process to exit (SIGTERM).
+    Terminate,
+    /// Force the process to exit (SIGKILL).
+    Kill,
+    /// Pause the process (SIGSTOP).
+    Suspend,
+    /// Continue a paused process (SIGCONT).
+    Resume,
+    /// Change the scheduling niceness, from -20 (highest priority) to 19.
+    Renice(i32),
+}
+
+impl ProcessAction {
+    /// Parses the action names used by the UI. A niceness outside -20..=19 is clamped
+    /// here, so the action that gets logged is the one that gets applied.
+    pub fn parse(name: &str, nice: i32) -> Option<Self> {
+        match name {
+            "terminate" => Some(ProcessAction::Terminate),
+            "kill" => Some(ProcessAction::Kill),
+            "suspend" => Some(ProcessAction::Suspend),
+            "resume" => Some(ProcessAction::Resume),
+            "renice" => Some(ProcessAction::Renice(nice.clamp(-20, 19))),
+            _ => None,
+        }
+    }
+
+    fn signal(self) -> Option<Signal> {
+        match self {
+            ProcessAction::Terminate => Some(Signal::Term),
+            ProcessAction::Kill => Some(Signal::Kill),
+            ProcessAction::Suspend => Some(Signal::Stop),
+            ProcessAction::Resume => Some(Signal::Continue),
+            ProcessAction::Renice(_) => None,
+        }
+    }
+}
+
+impl fmt::Display for ProcessAction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ProcessAction::Terminate => f.write_str("terminate (SIGTERM)"),
+            ProcessAction::Kill => f.write_str("kill (SIGKILL)"),
+            ProcessAction::Suspend => f.write_str("suspend (SIGSTOP)"),
+            ProcessAction::Resume => f.write_str("resume (SIGCONT)"),
+            ProcessAction::Renice(nice) => write!(f, "renice to {}", nice),
+        }
+    }
+}
+
+/// Why a process action failed.
+#[derive(Debug)]
+pub enum ActionError {
+    /// The process exited or never existed.
+    NotFound(u32),
+    /// The process belongs to another user and we lack the privileges to touch it.
+    PermissionDenied(u32),
+    /// The platform cannot perform this action.
+    Unsupported(ProcessAction),
+    Io(io::Error),
+}
+
+impl fmt::Display for ActionError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ActionError::NotFound(pid) => write!(f, "process {} no longer exists", pid),
+            ActionError::PermissionDenied(pid) => write!(
+                f,
+                "permission denied for process {}; it belongs to another user or needs elevated privileges",
+                pid
+            ),
+            ActionError::Unsupported(action) => {
+                write!(f, "cannot {} on this platform", action)
+            }
+            ActionError::Io(err) => write!(f, "{}", err),
+        }
+    }
+}
+
+impl Error for ActionError {}
+
+impl ActionError {
+    fn from_os_error(pid: u32, err: io::Error) -> Self {
+        match err.kind() {
+            io::ErrorKind::PermissionDenied => ActionError::PermissionDenied(pid),
+            io::ErrorKind::NotFound => ActionError::NotFound(pid),
+            _ if err.raw_os_error() == Some(ESRCH) => ActionError::NotFound(pid),
+            _ => ActionError::Io(err),
+        }
+    }
+}
+
+/// `errno` for "no such process", which std does not map to an `ErrorKind`.
+#[cfg(unix)]
+const ESRCH: i32 = libc::ESRCH;
+#[cfg(not(unix))]
+const ESRCH: i32 = -1;
+
+/// Applies `action` to the process with the given PID.
+pub fn perform(pid: u32, action: ProcessAction) -> Result<(), ActionError> {
+    match action.signal() {
+        Some(signal) => send_signal(pid, action, signal),
+        None => match action {
+            ProcessAction::Renice(nice) => renice(pid, nice),
+            _ => Err(ActionError::Unsupported(action)),
+        },
+    }
+}
+
+fn send_signal(pid: u32, action: ProcessAction, signal: Signal) -> Result<(), ActionError> {
+    let mut system = System::new();
+    let sys_pid = Pid::from_u32(pid);
+    if !system.refresh_process_specifics(sys_pid, ProcessRefreshKind::new()) {
+        return Err(ActionError::NotFound(pid));
+    }
+    let process = system.process(sys_pid).ok_or(ActionError::NotFound(pid))?;
+
+    match process.kill_with(signal) {
+        Some(true) => Ok(()),
+        // sysinfo only reports success or failure; errno still holds the reason
+        Some(false) => Err(ActionError::from_os_error(pid, io::Error::last_os_error())),
+        None => Err(ActionError::Unsupported(action)),
+    }
+}
+
+#[cfg(unix)]
+fn renice(pid: u32, nice: i32) -> Result<(), ActionError> {
+    // SAFETY: setpriority only reads its integer arguments
+    let result = unsafe { libc::setpriority(libc::PRIO_PROCESS, pid as libc::id_t, nice) };
+    if result == 0 {
+        Ok(())
+    } else {
+        Err(ActionError::from_os_error(pid, io::Error::last_os_error()))
+    }
+}
+
+#[cfg(not(unix))]
+fn renice(_pid: u32, nice: i32) -> Result<(), ActionError> {
+    Err(ActionError::Unsupported(ProcessAction::Renice(nice)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_clamps_niceness_to_the_valid_range() {
+        assert_eq!(
+            ProcessAction::parse("renice", -40),
+            Some(ProcessAction::Renice(-20))
+        );
+        assert_eq!(
+            ProcessAction::parse("renice", 25),
+            Some(ProcessAction::Renice(19))
+        );
+        assert_eq!(
+            ProcessAction::parse("renice", 5),
+            Some(ProcessAction::Renice(5))
+        );
+        assert_eq!(ProcessAction::parse("kill", 25), Some(ProcessAction::Kill));
+        assert_eq!(ProcessAction::parse("explode", 0), None);
+    }
+}
@@ -0,0 +1,319 @@
+use std::collections::{HashMap, HashSet};
+
+use crate::snapshot::ProcessSnapshot;
+
+/// Parent/child relationships of a process list, with usage aggregated per subtree.
+pub struct ProcessTree<'a> {
+    processes: HashMap<u32, &'a ProcessSnapshot>,
+    children: HashMap<u32, Vec<u32>>,
+    roots: Vec<u32>,
+    /// CPU and memory summed over each subtree.
+    totals: HashMap<u32, (f32, u64)>,
+}
+
+/// One visible line of the flattened tree.
+#[derive(Debug, Clone)]
+pub struct TreeLine<'a> {
+    pub process: &'a ProcessSnapshot,
+    pub depth: usize,
+    pub has_children: bool,
+    pub expanded: bool,
+    /// CPU usage of the process and all of its descendants.
+    pub subtree_cpu_percent: f32,
+    /// Memory of the process and all of its descendants, in bytes.
+    pub subtree_memory_bytes: u64,
+}
+
+impl<'a> ProcessTree<'a> {
+    pub fn build(processes: &'a [ProcessSnapshot]) -> Self {
+        let by_pid: HashMap<u32, &ProcessSnapshot> = processes
+            .iter()
+            .map(|process| (process.pid, process))
+            .collect();
+
+        let mut children: HashMap<u32, Vec<u32>> = HashMap::new();
+        let mut roots = Vec::new();
+        for process in processes {
+            match process
+                .parent_pid
+                .filter(|parent| by_pid.contains_key(parent))
+            {
+                Some(parent) if parent != process.pid => {
+                    children.entry(parent).or_default().push(process.pid)
+                }
+                // Orphans whose parent already exited are shown at the top level
+                _ => roots.push(process.pid),
+            }
+        }
+
+        for siblings in children.values_mut() {
+            siblings.sort_unstable();
+        }
+
+        // PID reuse can make parent links cyclic. Such a loop has no root, so it would
+        // never be listed; cut it where the walk up from its lowest PID comes back round.
+        let mut reachable = HashSet::new();
+        for root in &roots {
+            mark_reachable(*root, &children, &mut reachable);
+        }
+        let mut unreachable: Vec<u32> = by_pid
+            .keys()
+            .copied()
+            .filter(|pid| !reachable.contains(pid))
+            .collect();
+        unreachable.sort_unstable();
+        for pid in unreachable {
+            if reachable.contains(&pid) {
+                continue;
+            }
+            let mut seen = HashSet::new();
+            let mut current = pid;
+            while seen.insert(current) {
+                match by_pid[&current].parent_pid {
+                    Some(parent) => current = parent,
+                    None => break,
+                }
+            }
+            if let Some(siblings) = by_pid[&current]
+                .parent_pid
+                .and_then(|parent| children.get_mut(&parent))
+            {
+                siblings.retain(|child| *child != current);
+            }
+            roots.push(current);
+            mark_reachable(current, &children, &mut reachable);
+        }
+        roots.sort_unstable();
+
+        let mut tree = Self {
+            processes: by_pid,
+            children,
+            roots,
+            totals: HashMap::new(),
+        };
+        for root in tree.roots.clone() {
+            tree.compute_totals(root);
+        }
+        tree
+    }
+
+    /// Depth-first listing of the tree, skipping the descendants of collapsed processes.
+    pub fn lines(&self, collapsed: &HashSet<u32>) -> Vec<TreeLine<'a>> {
+        let mut lines = Vec::with_capacity(self.processes.len());
+        let mut visited = HashSet::new();
+        let mut stack: Vec<(u32, usize)> = self.roots.iter().rev().map(|pid| (*pid, 0)).collect();
+
+        while let Some((pid, depth)) = stack.pop() {
+            // Cycles are cut in `build`, but never visit a process twice regardless
+            if !visited.insert(pid) {
+                continue;
+            }
+            let Some(process) = self.processes.get(&pid) else {
+                continue;
+            };
+
+            let children = self
+                .children
+                .get(&pid)
+                .map(Vec::as_slice)
+                .unwrap_or_default();
+            let expanded = !collapsed.contains(&pid);
+            let (subtree_cpu_percent, subtree_memory_bytes) =
+                self.totals.get(&pid).copied().unwrap_or_default();
+
+            lines.push(TreeLine {
+                process,
+                depth,
+                has_children: !children.is_empty(),
+                expanded,
+                subtree_cpu_percent,
+                subtree_memory_bytes,
+            });
+
+            if expanded {
+                stack.extend(children.iter().rev().map(|child| (*child, depth + 1)));
+            }
+        }
+
+        lines
+    }
+
+    /// PIDs of a process and all of its descendants, each parent before its children.
+    pub fn subtree(&self, pid: u32) -> Vec<u32> {
+        let mut order = Vec::new();
+        let mut visited = HashSet::new();
+        let mut stack = vec![pid];
+        while let Some(pid) = stack.pop() {
+            if !visited.insert(pid) || !self.processes.contains_key(&pid) {
+                continue;
+            }
+            order.push(pid);
+            if let Some(children) = self.children.get(&pid) {
+                stack.extend(children.iter().rev());
+            }
+        }
+        order
+    }
+
+    fn compute_totals(&mut self, pid: u32) -> (f32, u64) {
+        if let Some(totals) = self.totals.get(&pid) {
+            return *totals;
+        }
+        let Some(process) = self.processes.get(&pid) else {
+            return (0.0, 0);
+        };
+
+        // Seed the entry first so recursion ends even if a cycle slipped through
+        let own = (process.cpu_percent, process.memory_bytes);
+        self.totals.insert(pid, own);
+
+        let children = self.children.get(&pid).cloned().unwrap_or_default();
+        let totals = children.into_iter().fold(own, |(cpu, memory), child| {
+            let (child_cpu, child_memory) = self.compute_totals(child);
+            (cpu + child_cpu, memory + child_memory)
+        });
+        self.totals.insert(pid, totals);
+        totals
+    }
+}
+
+/// Adds `pid` and everything below it to `reachable`.
+fn mark_reachable(pid: u32, children: &HashMap<u32, Vec<u32>>, reachable: &mut HashSet<u32>) {
+    let mut stack = vec![pid];
+    while let Some(pid) = stack.pop() {
+        if reachable.insert(pid) {
+            stack.extend(children.get(&pid).into_iter().flatten());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn process(pid: u32, parent_pid: Option<u32>) -> ProcessSnapshot {
+        ProcessSnapshot {
+            pid,
+            parent_pid,
+            ..Default::default()
+        }
+    }
+
+    fn usage(
+        pid: u32,
+        parent_pid: Option<u32>,
+        cpu_percent: f32,
+        memory_bytes: u64,
+    ) -> ProcessSnapshot {
+        ProcessSnapshot {
+            cpu_percent,
+            memory_bytes,
+            ..process(pid, parent_pid)
+        }
+    }
+
+    /// `(pid, depth)` of every listed line.
+    fn outline(tree: &ProcessTree, collapsed: &[u32]) -> Vec<(u32, usize)> {
+        let collapsed = collapsed.iter().copied().collect();
+        tree.lines(&collapsed)
+            .iter()
+            .map(|line| (line.process.pid, line.depth))
+            .collect()
+    }
+
+    #[test]
+    fn lines_walk_the_tree_depth_first_and_skip_collapsed_branches() {
+        let processes = [
+            process(20, Some(1)),
+            process(1, None),
+            process(12, Some(10)),
+            process(10, Some(1)),
+            process(11, Some(10)),
+            // Its parent already exited
+            process(30, Some(999)),
+        ];
+        let tree = ProcessTree::build(&processes);
+
+        assert_eq!(
+            outline(&tree, &[]),
+            [(1, 0), (10, 1), (11, 2), (12, 2), (20, 1), (30, 0)]
+        );
+        assert_eq!(outline(&tree, &[10]), [(1, 0), (10, 1), (20, 1), (30, 0)]);
+
+        let lines = tree.lines(&[10].into_iter().collect());
+        let branch = &lines[1];
+        assert!(branch.has_children);
+        assert!(!branch.expanded);
+        assert!(!lines[2].has_children);
+    }
+
+    #[test]
+    fn subtree_totals_add_up_every_descendant() {
+        let processes = [
+            usage(1, None, 1.0, 100),
+            usage(10, Some(1), 2.0, 200),
+            usage(11, Some(10), 4.0, 400),
+            usage(20, Some(1), 8.0, 800),
+        ];
+        let tree = ProcessTree::build(&processes);
+
+        let totals: Vec<_> = tree
+            .lines(&HashSet::new())
+            .iter()
+            .map(|line| {
+                (
+                    line.process.pid,
+                    line.subtree_cpu_percent,
+                    line.subtree_memory_bytes,
+                )
+            })
+            .collect();
+        assert_eq!(
+            totals,
+            [
+                (1, 15.0, 1500),
+                (10, 6.0, 600),
+                (11, 4.0, 400),
+                (20, 8.0, 800)
+            ]
+        );
+    }
+
+    #[test]
+    fn a_parent_cycle_without_a_root_is_still_listed() {
+        let processes = [
+            process(1, None),
+            usage(5, Some(6), 1.0, 10),
+            usage(6, Some(5), 2.0, 20),
+            usage(7, Some(6), 4.0, 40),
+            // Points at itself
+            process(9, Some(9)),
+        ];
+        let tree = ProcessTree::build(&processes);
+
+        // The walk up from 5 returns to 5, so the loop is cut above it
+        assert_eq!(
+            outline(&tree, &[]),
+            [(1, 0), (5, 0), (6, 1), (7, 2), (9, 0)]
+        );
+        let lines = tree.lines(&HashSet::new());
+        assert_eq!(lines[1].subtree_cpu_percent, 7.0);
+        assert_eq!(lines[1].subtree_memory_bytes, 70);
+        assert_eq!(tree.subtree(6), [6, 7]);
+    }
+
+    #[test]
+    fn subtree_lists_parents_before_children() {
+        let processes = [
+            process(1, None),
+            process(10, Some(1)),
+            process(11, Some(10)),
+            process(12, Some(10)),
+            process(20, Some(1)),
+        ];
+        let tree = ProcessTree::build(&processes);
+        assert_eq!(tree.subtree(10), [10, 11, 12]);
+        assert_eq!(tree.subtree(1), [1, 10, 11, 12, 20]);
+        assert!(tree.subtree(99).is_empty());
+    }
+}
@@ -0,0 +1,607 @@
+use std::collections::HashMap;
+use std::fmt;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+use rusqlite::{params, Connection};
+
+use crate::collector::Sample;
+use crate::monitor::Monitor;
+use crate::snapshot::{
+    CpuSnapshot, GpuSnapshot, GpuTelemetry, InterfaceSnapshot, MemorySnapshot, SensorKind,
+    SensorReading, StorageSnapshot, SystemSnapshot, VramUsage,
+};
+
+/// Layout version stored in `PRAGMA user_version`, described in `docs/recording.md`.
+pub const SCHEMA_VERSION: i32 = 1;
+
+/// Width of the fine and coarse rollup buckets.
+pub const MINUTE: Duration = Duration::from_secs(60);
+pub const HOUR: Duration = Duration::from_secs(60 * 60);
+
+/// How often rollups are brought up to date and expired rows are deleted.
+const MAINTENANCE_INTERVAL: Duration = Duration::from_secs(60);
+
+const SCHEMA: &str = "
+    CREATE TABLE IF NOT EXISTS samples (
+        timestamp INTEGER NOT NULL,
+        metric TEXT NOT NULL,
+        instance TEXT NOT NULL,
+        value REAL NOT NULL
+    );
+    CREATE INDEX IF NOT EXISTS samples_by_metric ON samples (metric, instance, timestamp);
+    CREATE INDEX IF NOT EXISTS samples_by_time ON samples (timestamp);
+
+    CREATE TABLE IF NOT EXISTS rollups (
+        resolution INTEGER NOT NULL,
+        bucket INTEGER NOT NULL,
+        metric TEXT NOT NULL,
+        instance TEXT NOT NULL,
+        count INTEGER NOT NULL,
+        min REAL NOT NULL,
+        max REAL NOT NULL,
+        avg REAL NOT NULL,
+        PRIMARY KEY (resolution, metric, instance, bucket)
+    );
+
+    CREATE TABLE IF NOT EXISTS info (
+        key TEXT NOT NULL,
+        instance TEXT NOT NULL,
+        value TEXT NOT NULL,
+        PRIMARY KEY (key, instance)
+    );
+";
+
+/// How long each level of detail is kept.
+#[derive(Debug, Clone, Copy)]
+pub struct Retention {
+    pub raw: Duration,
+    pub minute: Duration,
+    pub hour: Duration,
+}
+
+/// Failure to open or write the recording database.
+#[derive(Debug)]
+pub enum RecordError {
+    Open(PathBuf, rusqlite::Error),
+    Write(rusqlite::Error),
+}
+
+impl fmt::Display for RecordError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RecordError::Open(path, err) => {
+                write!(f, "could not open recording {}: {}", path.display(), err)
+            }
+            RecordError::Write(err) => write!(f, "could not write recording: {}", err),
+        }
+    }
+}
+
+impl std::error::Error for RecordError {}
+
+impl From<rusqlite::Error> for RecordError {
+    fn from(err: rusqlite::Error) -> Self {
+        RecordError::Write(err)
+    }
+}
+
+/// One scalar value taken from a snapshot, e.g. `cpu_usage_percent` of core `cpu3`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Reading {
+    pub metric: &'static str,
+    /// Core, device, interface or sensor the value belongs to; empty for machine-wide values.
+    pub instance: String,
+    pub value: f64,
+}
+
+impl Reading {
+    fn new(metric: &'static str, instance: impl Into<String>, value: f64) -> Self {
+        Self {
+            metric,
+            instance: instance.into(),
+            value,
+        }
+    }
+}
+
+pub fn sensor_metric(kind: SensorKind) -> &'static str {
+    match kind {
+        SensorKind::Temperature => "sensor_temperature_celsius",
+        SensorKind::Fan => "sensor_fan_rpm",
+        SensorKind::Voltage => "sensor_voltage_volts",
+        SensorKind::Power => "sensor_power_watts",
+    }
+}
+
+/// Flattens a snapshot into the values worth keeping. Processes are left out;
+/// there are too many of them and they come and go.
+pub fn readings(snapshot: &SystemSnapshot) -> Vec<Reading> {
+    let mut readings = Vec::new();
+    cpu_readings(&snapshot.cpu, &mut readings);
+    memory_readings(&snapshot.memory, &mut readings);
+    gpu_readings(&snapshot.gpus, &mut readings);
+    storage_readings(&snapshot.storage, &mut readings);
+    network_readings(&snapshot.networks, &mut readings);
+    sensor_readings(&snapshot.sensors, &mut readings);
+    readings
+}
+
+/// The values of a single collector's sample, named as in [`readings`].
+pub fn sample_readings(sample: &Sample) -> Vec<Reading> {
+    let mut readings = Vec::new();
+    match sample {
+        Sample::Cpu(cpu) => cpu_readings(cpu, &mut readings),
+        Sample::Memory(memory) => memory_readings(memory, &mut readings),
+        Sample::Gpu(gpus) => gpu_readings(gpus, &mut readings),
+        Sample::Storage(storage) => storage_readings(storage, &mut readings),
+        Sample::Network(interfaces) => network_readings(interfaces, &mut readings),
+        Sample::Sensors(sensors) => sensor_readings(sensors, &mut readings),
+        Sample::Processes(_) => {}
+    }
+    readings
+}
+
+fn cpu_readings(cpu: &CpuSnapshot, readings: &mut Vec<Reading>) {
+    let total = f64::from(cpu.usage_percent);
+    readings.push(Reading::new("cpu_usage_percent", "", total));
+    for core in &cpu.cores {
+        let usage = f64::from(core.usage_percent);
+        readings.push(Reading::new("cpu_usage_percent", &core.name, usage));
+        let frequency = core.frequency_mhz as f64;
+        readings.push(Reading::new("cpu_frequency_mhz", &core.name, frequency));
+    }
+}
+
+fn memory_readings(memory: &MemorySnapshot, readings: &mut Vec<Reading>) {
+    readings.extend([
+        Reading::new("memory_total_bytes", "", memory.total_bytes as f64),
+        Reading::new("memory_used_bytes", "", memory.used_bytes as f64),
+        Reading::new("memory_free_bytes", "", memory.free_bytes as f64),
+        Reading::new("swap_total_bytes", "", memory.swap_total_bytes as f64),
+        Reading::new("swap_used_bytes", "", memory.swap_used_bytes as f64),
+    ]);
+}
+
+fn gpu_readings(gpus: &[GpuSnapshot], readings: &mut Vec<Reading>) {
+    for (index, gpu) in gpus.iter().enumerate() {
+        let instance = index.to_string();
+        if let Some(vram) = &gpu.vram {
+            let total = vram.total_bytes as f64;
+            readings.push(Reading::new("gpu_vram_total_bytes", &instance, total));
+            if let Some(used) = vram.used_bytes {
+                readings.push(Reading::new("gpu_vram_used_bytes", &instance, used as f64));
+            }
+        }
+        let Some(telemetry) = &gpu.telemetry else {
+            continue;
+        };
+        let values = [
+            ("gpu_utilization_percent", telemetry.utilization_percent),
+            ("gpu_temperature_celsius", telemetry.temperature_celsius),
+            ("gpu_power_milliwatts", telemetry.power_draw_milliwatts),
+        ];
+        for (metric, value) in values {
+            if let Some(value) = value {
+                readings.push(Reading::new(metric, &instance, f64::from(value)));
+            }
+        }
+    }
+}
+
+fn storage_readings(storage: &StorageSnapshot, readings: &mut Vec<Reading>) {
+    for device in &storage.devices {
+        readings.extend([
+            Reading::new(
+                "disk_read_bytes_per_second",
+                &device.name,
+                device.read_bytes_per_sec as f64,
+            ),
+            Reading::new(
+                "disk_written_bytes_per_second",
+                &device.name,
+                device.write_bytes_per_sec as f64,
+            ),
+        ]);
+    }
+}
+
+fn network_readings(interfaces: &[InterfaceSnapshot], readings: &mut Vec<Reading>) {
+    for interface in interfaces {
+        readings.extend([
+            Reading::new(
+                "network_received_bytes_per_second",
+                &interface.name,
+                interface.received_bytes_per_sec as f64,
+            ),
+            Reading::new(
+                "network_transmitted_bytes_per_second",
+                &interface.name,
+                interface.transmitted_bytes_per_sec as f64,
+            ),
+        ]);
+    }
+}
+
+fn sensor_readings(sensors: &[SensorReading], readings: &mut Vec<Reading>) {
+    for sensor in sensors {
+        let metric = sensor_metric(sensor.kind);
+        readings.push(Reading::new(metric, &sensor.label, sensor.value));
+    }
+}
+
+fn unix_millis(time: SystemTime) -> i64 {
+    time.duration_since(UNIX_EPOCH)
+        .map_or(0, |since| since.as_millis() as i64)
+}
+
+/// Writes collected samples to a SQLite database and keeps its rollups and retention up to date.
+pub struct Recorder {
+    connection: Connection,
+    retention: Retention,
+    last_maintenance: Option<Instant>,
+    /// `info` rows as last written, keyed by key and instance, so unchanged ones are skipped.
+    info: HashMap<(&'static str, String), String>,
+}
+
+impl Recorder {
+    /// Opens or creates a recording, upgrading an empty file to the current schema.
+    pub fn open(path: &Path, retention: Retention) -> Result<Self, RecordError> {
+        let open_error = |err| RecordError::Open(path.to_path_buf(), err);
+        let connection = Connection::open(path).map_err(open_error)?;
+        // WAL lets other processes query the file while it is being recorded
+        connection
+            .pragma_update(None, "journal_mode", "WAL")
+            .map_err(open_error)?;
+        connection.execute_batch(SCHEMA).map_err(open_error)?;
+        connection
+            .pragma_update(None, "user_version", SCHEMA_VERSION)
+            .map_err(open_error)?;
+
+        Ok(Self {
+            connection,
+            retention,
+            last_maintenance: None,
+            info: HashMap::new(),
+        })
+    }
+
+    /// Stores the samples of one poll, then runs maintenance if it is due. Sections whose
+    /// collectors did not run are left alone, so slow collectors are not counted twice.
+    pub fn record(&mut self, timestamp: SystemTime, samples: &[Sample]) -> Result<(), RecordError> {
+        let timestamp = unix_millis(timestamp);
+        let transaction = self.connection.transaction()?;
+        {
+            let mut insert = transaction.prepare_cached(
+                "INSERT INTO samples (timestamp, metric, instance, value) VALUES (?1, ?2, ?3, ?4)",
+            )?;
+            for reading in samples.iter().flat_map(sample_readings) {
+                insert.execute(params![
+                    timestamp,
+                    reading.metric,
+                    reading.instance,
+                    reading.value
+                ])?;
+            }
+
+            // Names that replay needs but that are not numbers
+            let mut info = transaction.prepare_cached(
+                "INSERT OR REPLACE INTO info (key, instance, value) VALUES (?1, ?2, ?3)",
+            )?;
+            for (key, instance, value) in samples.iter().flat_map(info_rows) {
+                let previous = self.info.get(&(key, instance.clone()));
+                if previous != Some(&value) {
+                    info.execute(params![key, instance, value])?;
+                    self.info.insert((key, instance), value);
+                }
+            }
+        }
+        transaction.commit()?;
+
+        let now = Instant::now();
+        let due = self
+            .last_maintenance
+            .is_none_or(|last| now.duration_since(last) >= MAINTENANCE_INTERVAL);
+        if due {
+            self.maintain(SystemTime::now())?;
+            self.last_maintenance = Some(now);
+        }
+        Ok(())
+    }
+
+    /// Rolls every finished bucket up to minute and hour aggregates, then drops
+    /// whatever is older than its retention.
+    pub fn maintain(&mut self, now: SystemTime) -> Result<(), RecordError> {
+        let now = unix_millis(now);
+        let minute = MINUTE.as_millis() as i64;
+        let hour = HOUR.as_millis() as i64;
+
+        let transaction = self.connection.transaction()?;
+
+        // Only complete buckets are rolled up, continuing after the newest one already stored
+        let start = next_bucket(&transaction, minute)?;
+        transaction.execute(
+            "INSERT OR REPLACE INTO rollups (resolution, bucket, metric, instance, count, min, max, avg)
+             SELECT ?1, timestamp - timestamp % ?1 AS start, metric, instance,
+                    COUNT(*), MIN(value), MAX(value), AVG(value)
+             FROM samples
+             WHERE timestamp >= ?2 AND timestamp < ?3
+             GROUP BY start, metric, instance",
+            params![minute, start, now - now % minute],
+        )?;
+
+        // Hours are built from the minute rollups, weighting each minute by its sample count
+        let start = next_bucket(&transaction, hour)?;
+        transaction.execute(
+            "INSERT OR REPLACE INTO rollups (resolution, bucket, metric, instance, count, min, max, avg)
+             SELECT ?1, bucket - bucket % ?1 AS start, metric, instance,
+                    SUM(count), MIN(min), MAX(max), SUM(avg * count) / SUM(count)
+             FROM rollups
+             WHERE resolution = ?2 AND bucket >= ?3 AND bucket < ?4
+             GROUP BY start, metric, instance",
+            params![hour, minute, start, now - now % hour],
+        )?;
+
+        let raw_cutoff = now - self.retention.raw.as_millis() as i64;
+        transaction.execute("DELETE FROM samples WHERE timestamp < ?1", [raw_cutoff])?;
+        for (resolution, retention) in
+            [(minute, self.retention.minute), (hour, self.retention.hour)]
+        {
+            transaction.execute(
+                "DELETE FROM rollups WHERE resolution = ?1 AND bucket < ?2",
+                [resolution, now - retention.as_millis() as i64],
+            )?;
+        }
+
+        transaction.commit()?;
+        Ok(())
+    }
+}
+
+/// `info` rows describing a sample: the CPU's names, and one JSON description per GPU.
+fn info_rows(sample: &Sample) -> Vec<(&'static str, String, String)> {
+    match sample {
+        Sample::Cpu(cpu) => vec![
+            ("cpu_brand", String::new(), cpu.brand.clone()),
+            ("cpu_vendor_id", String::new(), cpu.vendor_id.clone()),
+        ],
+        // Live telemetry is stored with it, but only changes to the rest cause a rewrite
+        Sample::Gpu(gpus) => gpus
+            .iter()
+            .enumerate()
+            .filter_map(|(index, gpu)| {
+                let identity = GpuSnapshot {
+                    vram: gpu.vram.clone().map(|vram| VramUsage {
+                        used_bytes: None,
+                        ..vram
+                    }),
+                    telemetry: gpu.telemetry.as_ref().map(|_| GpuTelemetry::default()),
+                    ..gpu.clone()
+                };
+                let description = serde_json::to_string(&identity).ok()?;
+                Some(("gpu", index.to_string(), description))
+            })
+            .collect(),
+        _ => Vec::new(),
+    }
+}
+
+/// Start of the first bucket of `resolution` that has not been rolled up yet.
+fn next_bucket(connection: &Connection, resolution: i64) -> rusqlite::Result<i64> {
+    connection.query_row(
+        "SELECT COALESCE(MAX(bucket) + ?1, 0) FROM rollups WHERE resolution = ?1",
+        [resolution],
+        |row| row.get(0),
+    )
+}
+
+/// Records every poll of the monitor until the monitor goes away.
+pub fn spawn(mut recorder: Recorder, monitor: Arc<Mutex<Monitor>>) {
+    tokio::spawn(async move {
+        let Ok(mut polls) = monitor.lock().map(|mut monitor| monitor.tap()) else {
+            return;
+        };
+
+        while let Some((timestamp, samples)) = polls.recv().await {
+            // SQLite blocks; keep it off the threads that drive the async tasks
+            let result = tokio::task::block_in_place(|| recorder.record(timestamp, &samples));
+            if let Err(err) = result {
+                log::error!("{}", err);
+            }
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::snapshot::{GpuDeviceType, VramSource};
+
+    fn retention() -> Retention {
+        Retention {
+            raw: Duration::from_secs(24 * 60 * 60),
+            minute: Duration::from_secs(30 * 24 * 60 * 60),
+            hour: Duration::from_secs(365 * 24 * 60 * 60),
+        }
+    }
+
+    fn cpu(usage_percent: f32) -> Sample {
+        Sample::Cpu(CpuSnapshot {
+            brand: "Test CPU".to_string(),
+            usage_percent,
+            ..Default::default()
+        })
+    }
+
+    fn gpu(utilization: u32, used_bytes: u64) -> Sample {
+        Sample::Gpu(vec![GpuSnapshot {
+            name: "Test GPU".to_string(),
+            device_type: GpuDeviceType::Discrete,
+            backends: vec!["Vulkan".to_string()],
+            vendor_id: 0x10de,
+            device_id: 0x2484,
+            driver: String::new(),
+            driver_version: String::new(),
+            max_storage_buffer_binding_bytes: 0,
+            vram: Some(VramUsage {
+                total_bytes: 8 << 30,
+                used_bytes: Some(used_bytes),
+                source: VramSource::Nvml,
+            }),
+            telemetry: Some(GpuTelemetry {
+                utilization_percent: Some(utilization),
+                ..Default::default()
+            }),
+        }])
+    }
+
+    fn count(recorder: &Recorder, sql: &str) -> i64 {
+        recorder
+            .connection
+            .query_row(sql, [], |row| row.get(0))
+            .unwrap()
+    }
+
+    #[test]
+    fn only_the_sections_of_each_poll_are_stored() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut recorder = Recorder::open(&dir.path().join("rec.db"), retention()).unwrap();
+        let start = SystemTime::now();
+
+        // CPU every second, the GPU every other second
+        recorder
+            .record(start, &[cpu(10.0), gpu(50, 1 << 30)])
+            .unwrap();
+        recorder
+            .record(start + Duration::from_secs(1), &[cpu(20.0)])
+            .unwrap();
+        recorder
+            .record(
+                start + Duration::from_secs(2),
+                &[cpu(30.0), gpu(70, 2 << 30)],
+            )
+            .unwrap();
+
+        let rows = |metric: &str| {
+            count(
+                &recorder,
+                &format!("SELECT COUNT(*) FROM samples WHERE metric = '{}'", metric),
+            )
+        };
+        assert_eq!(rows("cpu_usage_percent"), 3);
+        assert_eq!(rows("gpu_utilization_percent"), 2);
+        assert_eq!(rows("memory_used_bytes"), 0);
+    }
+
+    #[test]
+    fn info_is_rewritten_only_when_the_description_changes() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut recorder = Recorder::open(&dir.path().join("rec.db"), retention()).unwrap();
+        let start = SystemTime::now();
+
+        recorder
+            .record(start, &[cpu(10.0), gpu(50, 1 << 30)])
+            .unwrap();
+        let before = recorder.connection.total_changes();
+        recorder
+            .record(
+                start + Duration::from_secs(2),
+                &[cpu(30.0), gpu(70, 2 << 30)],
+            )
+            .unwrap();
+        // One CPU row plus VRAM total, VRAM used and utilization; no info rows
+        assert_eq!(recorder.connection.total_changes() - before, 4);
+
+        let description: String = recorder
+            .connection
+            .query_row("SELECT value FROM info WHERE key = 'gpu'", [], |row| {
+                row.get(0)
+            })
+            .unwrap();
+        let stored: GpuSnapshot = serde_json::from_str(&description).unwrap();
+        assert_eq!(stored.name, "Test GPU");
+        assert_eq!(stored.vram.unwrap().used_bytes, None);
+        assert_eq!(stored.telemetry.unwrap().utilization_percent, None);
+    }
+
+    /// `(bucket offset from start in seconds, count, min, max, avg)` of the CPU rollups.
+    fn rollups(
+        recorder: &Recorder,
+        resolution: Duration,
+        start: i64,
+    ) -> Vec<(i64, i64, f64, f64, f64)> {
+        let mut query = recorder
+            .connection
+            .prepare(
+                "SELECT bucket, count, min, max, avg FROM rollups
+                 WHERE resolution = ?1 AND metric = 'cpu_usage_percent'
+                 ORDER BY bucket",
+            )
+            .unwrap();
+        query
+            .query_map([resolution.as_millis() as i64], |row| {
+                let bucket: i64 = row.get(0)?;
+                Ok((
+                    (bucket - start) / 1000,
+                    row.get(1)?,
+                    row.get(2)?,
+                    row.get(3)?,
+                    row.get(4)?,
+                ))
+            })
+            .unwrap()
+            .collect::<Result<_, _>>()
+            .unwrap()
+    }
+
+    #[test]
+    fn maintenance_rolls_up_finished_buckets_and_drops_expired_samples() {
+        let dir = tempfile::tempdir().unwrap();
+        let retention = Retention {
+            raw: HOUR,
+            ..retention()
+        };
+        let mut recorder = Recorder::open(&dir.path().join("rec.db"), retention).unwrap();
+        // Keep `record` from running maintenance against the real clock
+        recorder.last_maintenance = Some(Instant::now());
+
+        // On an hour boundary
+        let start = UNIX_EPOCH + Duration::from_secs(1_699_999_200);
+        let at = |secs| start + Duration::from_secs(secs);
+        recorder.record(at(0), &[cpu(10.0)]).unwrap();
+        recorder.record(at(30), &[cpu(20.0)]).unwrap();
+        recorder.record(at(90), &[cpu(60.0)]).unwrap();
+        recorder.record(at(3600 + 45), &[cpu(100.0)]).unwrap();
+        // Still in the unfinished third hour
+        recorder.record(at(2 * 3600 + 10), &[cpu(0.0)]).unwrap();
+
+        recorder.maintain(at(2 * 3600 + 30)).unwrap();
+
+        let start = unix_millis(start);
+        assert_eq!(
+            rollups(&recorder, MINUTE, start),
+            [
+                (0, 2, 10.0, 20.0, 15.0),
+                (60, 1, 60.0, 60.0, 60.0),
+                (3600, 1, 100.0, 100.0, 100.0),
+            ]
+        );
+        // Weighted by sample count: (10 + 20 + 60) / 3, not the mean of 15 and 60
+        assert_eq!(
+            rollups(&recorder, HOUR, start),
+            [(0, 3, 10.0, 60.0, 30.0), (3600, 1, 100.0, 100.0, 100.0)]
+        );
+
+        // Only samples from the last hour are kept raw
+        let kept: Vec<i64> = recorder
+            .connection
+            .prepare("SELECT timestamp FROM samples ORDER BY timestamp")
+            .unwrap()
+            .query_map([], |row| row.get(0))
+            .unwrap()
+            .map(|timestamp: rusqlite::Result<i64>| (timestamp.unwrap() - start) / 1000)
+            .collect();
+        assert_eq!(kept, [3600 + 45, 2 * 3600 + 10]);
+    }
+}
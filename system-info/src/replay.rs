@@ -0,0 +1,312 @@
+use std::cell::RefCell;
+use std::error::Error;
+use std::path::Path;
+use std::rc::Rc;
+use std::time::Duration;
+
+use chrono::{DateTime, Local};
+use slint::{ComponentHandle, SharedString, Timer, TimerMode};
+
+use crate::collector::CollectorRegistry;
+use crate::export;
+use crate::monitor::Monitor;
+use crate::{ui, AppWindow};
+
+mod recording;
+
+use recording::{Recording, ReplayError};
+
+/// How often the playback clock advances.
+const TICK: Duration = Duration::from_millis(100);
+
+/// Recording time replayed before the current frame after a seek, so the charts have context.
+const CHART_WINDOW: Duration = Duration::from_secs(10 * 60);
+
+/// Longest stretch of recording time that playback sits through; longer gaps,
+/// such as the recorder being stopped, are skipped.
+const MAX_GAP: Duration = Duration::from_secs(5);
+
+/// Plays a recording into a [`Monitor`] that has no collectors of its own,
+/// so the UI renders it exactly like live data.
+struct Player {
+    recording: Recording,
+    monitor: Monitor,
+    index: usize,
+    /// Playback position in recording time, Unix milliseconds.
+    clock: i64,
+}
+
+impl Player {
+    fn new(recording: Recording) -> Result<Self, ReplayError> {
+        let mut player = Self {
+            clock: recording.timestamp(0),
+            recording,
+            monitor: Monitor::new(CollectorRegistry::new()),
+            index: 0,
+        };
+        player.seek(0)?;
+        Ok(player)
+    }
+
+    /// Jumps to a frame, rebuilding the charts from the frames shortly before it.
+    fn seek(&mut self, index: usize) -> Result<(), ReplayError> {
+        let index = index.min(self.recording.len() - 1);
+        let target = self.recording.timestamp(index);
+        let first = self
+            .recording
+            .index_at(target - CHART_WINDOW.as_millis() as i64);
+
+        self.monitor = Monitor::new(CollectorRegistry::new());
+        self.ingest(first, index)?;
+        self.index = index;
+        self.clock = target;
+        Ok(())
+    }
+
+    /// Moves the clock on by `elapsed` at `speed` and applies every frame it passed.
+    /// Returns `true` if the current frame changed.
+    fn advance(&mut self, elapsed: Duration, speed: f32) -> Result<bool, ReplayError> {
+        if self.at_end() {
+            return Ok(false);
+        }
+
+        self.clock += (elapsed.as_millis() as f32 * speed) as i64;
+        let next = self.recording.timestamp(self.index + 1);
+        if next - self.clock > MAX_GAP.as_millis() as i64 {
+            self.clock = next;
+        }
+
+        let index = self.recording.index_at(self.clock);
+        if index <= self.index {
+            return Ok(false);
+        }
+        self.ingest(self.index + 1, index)?;
+        self.index = index;
+        Ok(true)
+    }
+
+    fn ingest(&mut self, first: usize, last: usize) -> Result<(), ReplayError> {
+        for frame in self.recording.frames(first, last)? {
+            self.monitor.ingest_at(frame.samples, frame.timestamp);
+        }
+        Ok(())
+    }
+
+    fn at_end(&self) -> bool {
+        self.index + 1 >= self.recording.len()
+    }
+}
+
+/// Shows the current frame and where it sits in the recording.
+fn render(ui: &AppWindow, player: &Player) {
+    let time = DateTime::<Local>::from(player.monitor.snapshot().timestamp);
+    ui.set_replay_position(player.index as f32);
+    ui.set_replay_time(
+        format!(
+            "{} ({} / {})",
+            time.format("%Y-%m-%d %H:%M:%S"),
+            player.index + 1,
+            player.recording.len()
+        )
+        .into(),
+    );
+    ui::render(ui, &player.monitor.view());
+}
+
+/// Opens the window on a recording instead of live data.
+pub fn run(path: &Path) -> Result<(), Box<dyn Error>> {
+    let player = Rc::new(RefCell::new(Player::new(Recording::open(path)?)?));
+
+    let ui = AppWindow::new()?;
+    ui.set_replay_active(true);
+    ui.set_replay_length(player.borrow().recording.len() as i32);
+    render(&ui, &player.borrow());
+
+    // Advance the playback clock while playing
+    let timer = Timer::default();
+    timer.start(TimerMode::Repeated, TICK, {
+        let ui_handle = ui.as_weak();
+        let player = Rc::clone(&player);
+
+        move || {
+            let Some(ui) = ui_handle.upgrade() else {
+                return;
+            };
+            if !ui.get_replay_playing() {
+                return;
+            }
+
+            let mut player = player.borrow_mut();
+            match player.advance(TICK, ui.get_replay_speed()) {
+                Ok(true) => render(&ui, &player),
+                Ok(false) => {}
+                Err(err) => {
+                    log::error!("{}", err);
+                    ui.set_replay_playing(false);
+                }
+            }
+            if player.at_end() {
+                ui.set_replay_playing(false);
+            }
+        }
+    });
+
+    // Jump to wherever the timeline scrubber was dragged
+    ui.on_replay_seek({
+        let ui_handle = ui.as_weak();
+        let player = Rc::clone(&player);
+
+        move |index| {
+            let Some(ui) = ui_handle.upgrade() else {
+                return;
+            };
+            let mut player = player.borrow_mut();
+            match player.seek(index.max(0) as usize) {
+                Ok(()) => render(&ui, &player),
+                Err(err) => log::error!("{}", err),
+            }
+        }
+    });
+
+    // Recordings have no processes, but keep the table controls consistent
+    ui.on_process_view_changed({
+        let ui_handle = ui.as_weak();
+        let player = Rc::clone(&player);
+
+        move || {
+            if let Some(ui) = ui_handle.upgrade() {
+                ui::render_processes(&ui, &player.borrow().monitor.snapshot().processes);
+            }
+        }
+    });
+
+    // Copy the frame on screen
+    ui.on_snapshot_json({
+        let player = Rc::clone(&player);
+
+        move || {
+            let player = player.borrow();
+            match export::to_string(player.monitor.snapshot(), export::Format::Json) {
+                Ok(json) => json.into(),
+                Err(err) => {
+                    log::error!("{}", err);
+                    SharedString::new()
+                }
+            }
+        }
+    });
+
+    ui.run()?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::UNIX_EPOCH;
+
+    use rusqlite::{params, Connection};
+    use tempfile::TempDir;
+
+    use super::*;
+    use crate::history::{HistoryRange, Metric};
+    use crate::recorder::{Recorder, Retention};
+
+    const START: u64 = 1_700_000_000;
+
+    /// A recording of the total CPU usage at the given seconds after `START`.
+    fn recording(usage: &[(u64, f64)]) -> (TempDir, Recording) {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("rec.db");
+        let day = Duration::from_secs(24 * 60 * 60);
+        let retention = Retention {
+            raw: day,
+            minute: day,
+            hour: day,
+        };
+        drop(Recorder::open(&path, retention).unwrap());
+
+        let connection = Connection::open(&path).unwrap();
+        for (secs, value) in usage {
+            connection
+                .execute(
+                    "INSERT INTO samples VALUES (?1, 'cpu_usage_percent', '', ?2)",
+                    params![((START + secs) * 1000) as i64, value],
+                )
+                .unwrap();
+        }
+        drop(connection);
+
+        let recording = Recording::open(&path).unwrap();
+        (dir, recording)
+    }
+
+    fn cpu(player: &Player) -> f32 {
+        player.monitor.snapshot().cpu.usage_percent
+    }
+
+    fn charted(player: &Player) -> usize {
+        let series = player.monitor.history().series(Metric::Cpu);
+        series.points(HistoryRange::Recent).iter().len()
+    }
+
+    #[test]
+    fn advance_steps_through_frames_and_jumps_long_gaps() {
+        // The recorder was stopped between the third and fourth frames
+        let (_dir, recording) =
+            recording(&[(0, 0.0), (1, 10.0), (2, 20.0), (20, 30.0), (21, 40.0)]);
+        let mut player = Player::new(recording).unwrap();
+        assert_eq!((player.index, cpu(&player)), (0, 0.0));
+
+        assert!(!player.advance(Duration::from_millis(500), 1.0).unwrap());
+        assert!(player.advance(Duration::from_millis(500), 1.0).unwrap());
+        assert_eq!((player.index, cpu(&player)), (1, 10.0));
+
+        // Several seconds at once land on the last frame they pass
+        assert!(player.advance(Duration::from_secs(3), 1.0).unwrap());
+        assert_eq!((player.index, cpu(&player)), (2, 20.0));
+
+        // Sixteen seconds to the next frame is more than MAX_GAP, so it is shown right away
+        assert!(player.advance(Duration::from_millis(100), 1.0).unwrap());
+        assert_eq!((player.index, cpu(&player)), (3, 30.0));
+
+        assert!(player.advance(Duration::from_millis(500), 2.0).unwrap());
+        assert_eq!((player.index, cpu(&player)), (4, 40.0));
+        assert!(player.at_end());
+        assert!(!player.advance(Duration::from_secs(1), 1.0).unwrap());
+        assert_eq!(charted(&player), 5);
+    }
+
+    #[test]
+    fn seek_rebuilds_the_charts_up_to_the_target() {
+        let (_dir, recording) = recording(&[(0, 0.0), (1, 10.0), (2, 20.0), (3, 30.0)]);
+        let mut player = Player::new(recording).unwrap();
+
+        // Past the end stops at the last frame
+        player.seek(99).unwrap();
+        assert_eq!((player.index, cpu(&player)), (3, 30.0));
+        assert_eq!(charted(&player), 4);
+
+        // Going back drops the frames after the target
+        player.seek(1).unwrap();
+        assert_eq!((player.index, cpu(&player)), (1, 10.0));
+        assert_eq!(charted(&player), 2);
+        assert_eq!(
+            player.monitor.snapshot().timestamp,
+            UNIX_EPOCH + Duration::from_secs(START + 1)
+        );
+
+        // Playback carries on from there
+        assert!(player.advance(Duration::from_secs(1), 1.0).unwrap());
+        assert_eq!((player.index, cpu(&player)), (2, 20.0));
+    }
+
+    #[test]
+    fn seek_keeps_only_the_chart_window_before_the_target() {
+        let (_dir, recording) = recording(&[(0, 0.0), (1, 10.0), (600, 20.0), (601, 30.0)]);
+        let mut player = Player::new(recording).unwrap();
+
+        // Ten minutes before the last frame is second 1, so the frame at second 0 is left out
+        player.seek(3).unwrap();
+        assert_eq!(charted(&player), 3);
+    }
+}
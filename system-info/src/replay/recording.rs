@@ -0,0 +1,576 @@
+use std::collections::{BTreeMap, HashMap};
+use std::fmt;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use rusqlite::{Connection, OpenFlags};
+
+use crate::collector::Sample;
+use crate::recorder::{self, HOUR, MINUTE, SCHEMA_VERSION};
+use crate::snapshot::{
+    BlockDeviceSnapshot, CoreSnapshot, CpuSnapshot, GpuSnapshot, GpuTelemetry, InterfaceSnapshot,
+    MemorySnapshot, SensorKind, SensorReading, StorageSnapshot,
+};
+
+const SENSOR_KINDS: [SensorKind; 4] = [
+    SensorKind::Temperature,
+    SensorKind::Fan,
+    SensorKind::Voltage,
+    SensorKind::Power,
+];
+
+/// Failure to open or read a recording.
+#[derive(Debug)]
+pub enum ReplayError {
+    Open(PathBuf, rusqlite::Error),
+    Read(rusqlite::Error),
+    UnsupportedVersion(i32),
+    Empty,
+}
+
+impl fmt::Display for ReplayError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ReplayError::Open(path, err) => {
+                write!(f, "could not open recording {}: {}", path.display(), err)
+            }
+            ReplayError::Read(err) => write!(f, "could not read recording: {}", err),
+            ReplayError::UnsupportedVersion(0) => {
+                f.write_str("file is not a system-info recording")
+            }
+            ReplayError::UnsupportedVersion(version) => write!(
+                f,
+                "recording uses schema version {}, this build reads version {}",
+                version, SCHEMA_VERSION
+            ),
+            ReplayError::Empty => f.write_str("recording contains no data"),
+        }
+    }
+}
+
+impl std::error::Error for ReplayError {}
+
+impl From<rusqlite::Error> for ReplayError {
+    fn from(err: rusqlite::Error) -> Self {
+        ReplayError::Read(err)
+    }
+}
+
+/// A level of detail in a recording. Raw samples are kept the shortest, so the older
+/// stretches of a recording are only left as rollups.
+#[derive(Debug, Clone, Copy)]
+enum Source {
+    Samples,
+    Rollups(Duration),
+}
+
+impl Source {
+    /// Runs `query` with the resolution bound as `?1` for rollups, followed by `rest`.
+    fn query_map<T>(
+        self,
+        connection: &Connection,
+        query: (&str, &str),
+        rest: &[i64],
+        mut f: impl FnMut(&rusqlite::Row<'_>) -> rusqlite::Result<T>,
+    ) -> rusqlite::Result<Vec<T>> {
+        let (sql, resolution) = match self {
+            Source::Samples => (query.0, None),
+            Source::Rollups(width) => (query.1, Some(width.as_millis() as i64)),
+        };
+        let params: Vec<i64> = resolution.into_iter().chain(rest.iter().copied()).collect();
+
+        let mut statement = connection.prepare_cached(sql)?;
+        let mut rows = statement.query(rusqlite::params_from_iter(params))?;
+        let mut results = Vec::new();
+        while let Some(row) = rows.next()? {
+            results.push(f(row)?);
+        }
+        Ok(results)
+    }
+}
+
+const TIMESTAMPS: (&str, &str) = (
+    "SELECT DISTINCT timestamp FROM samples WHERE timestamp < ?1 ORDER BY timestamp",
+    "SELECT DISTINCT bucket FROM rollups WHERE resolution = ?1 AND bucket < ?2 ORDER BY bucket",
+);
+
+const FRAMES: (&str, &str) = (
+    "SELECT timestamp, metric, instance, value FROM samples
+     WHERE timestamp BETWEEN ?1 AND ?2 ORDER BY timestamp",
+    "SELECT bucket, metric, instance, avg FROM rollups
+     WHERE resolution = ?1 AND bucket BETWEEN ?2 AND ?3 ORDER BY bucket",
+);
+
+/// One recorded tick, as the collector samples that produced it.
+pub struct Frame {
+    pub timestamp: SystemTime,
+    pub samples: Vec<Sample>,
+}
+
+/// Frames from `first` onwards, up to the next segment, are read from `source`.
+#[derive(Debug, Clone, Copy)]
+struct Segment {
+    source: Source,
+    first: usize,
+}
+
+/// A database written by `--record`, opened read-only.
+pub struct Recording {
+    connection: Connection,
+    /// Oldest first: hour rollups, then minute rollups, then raw samples, each
+    /// covering only the time before the next, finer one begins.
+    segments: Vec<Segment>,
+    /// Start of every frame, Unix milliseconds, oldest first.
+    timestamps: Vec<i64>,
+    cpu_brand: String,
+    cpu_vendor_id: String,
+    gpus: BTreeMap<usize, GpuSnapshot>,
+}
+
+impl Recording {
+    pub fn open(path: &Path) -> Result<Self, ReplayError> {
+        let open_error = |err| ReplayError::Open(path.to_path_buf(), err);
+        let connection = Connection::open_with_flags(path, OpenFlags::SQLITE_OPEN_READ_ONLY)
+            .map_err(open_error)?;
+        let version: i32 = connection
+            .pragma_query_value(None, "user_version", |row| row.get(0))
+            .map_err(open_error)?;
+        if version != SCHEMA_VERSION {
+            return Err(ReplayError::UnsupportedVersion(version));
+        }
+
+        // Start from the raw samples, then fill the time before them from ever coarser rollups
+        let mut levels = Vec::new();
+        let mut end = i64::MAX;
+        for source in [
+            Source::Samples,
+            Source::Rollups(MINUTE),
+            Source::Rollups(HOUR),
+        ] {
+            let timestamps: Vec<i64> =
+                source.query_map(&connection, TIMESTAMPS, &[end], |row| row.get(0))?;
+            if let Some(first) = timestamps.first() {
+                end = *first;
+                levels.push((source, timestamps));
+            }
+        }
+        if levels.is_empty() {
+            return Err(ReplayError::Empty);
+        }
+
+        let mut segments = Vec::new();
+        let mut all = Vec::new();
+        for (source, timestamps) in levels.into_iter().rev() {
+            segments.push(Segment {
+                source,
+                first: all.len(),
+            });
+            all.extend(timestamps);
+        }
+
+        let mut recording = Self {
+            connection,
+            segments,
+            timestamps: all,
+            cpu_brand: String::new(),
+            cpu_vendor_id: String::new(),
+            gpus: BTreeMap::new(),
+        };
+        recording.load_info()?;
+        Ok(recording)
+    }
+
+    fn load_info(&mut self) -> rusqlite::Result<()> {
+        let mut statement = self
+            .connection
+            .prepare("SELECT key, instance, value FROM info")?;
+        let rows = statement.query_map([], |row| {
+            Ok((
+                row.get::<_, String>(0)?,
+                row.get::<_, String>(1)?,
+                row.get::<_, String>(2)?,
+            ))
+        })?;
+
+        for row in rows {
+            let (key, instance, value) = row?;
+            match key.as_str() {
+                "cpu_brand" => self.cpu_brand = value,
+                "cpu_vendor_id" => self.cpu_vendor_id = value,
+                "gpu" => {
+                    let (Ok(index), Ok(gpu)) = (instance.parse(), serde_json::from_str(&value))
+                    else {
+                        log::warn!("Skipping unreadable GPU description in recording");
+                        continue;
+                    };
+                    self.gpus.insert(index, gpu);
+                }
+                _ => {}
+            }
+        }
+        Ok(())
+    }
+
+    /// Number of frames.
+    pub fn len(&self) -> usize {
+        self.timestamps.len()
+    }
+
+    /// Time of a frame, Unix milliseconds.
+    pub fn timestamp(&self, index: usize) -> i64 {
+        self.timestamps[index.min(self.timestamps.len() - 1)]
+    }
+
+    /// Index of the last frame at or before `millis`, or the first frame if none is.
+    pub fn index_at(&self, millis: i64) -> usize {
+        self.timestamps
+            .partition_point(|timestamp| *timestamp <= millis)
+            .saturating_sub(1)
+    }
+
+    /// Frames `first..=last`, read with one query per level of detail they span.
+    pub fn frames(&self, first: usize, last: usize) -> Result<Vec<Frame>, ReplayError> {
+        let mut frames = Vec::new();
+        for (index, segment) in self.segments.iter().enumerate() {
+            let end = self
+                .segments
+                .get(index + 1)
+                .map_or(self.len(), |next| next.first);
+            let (from, to) = (first.max(segment.first), last.min(end - 1));
+            if from <= to {
+                frames.extend(self.read_frames(segment.source, from, to)?);
+            }
+        }
+        Ok(frames)
+    }
+
+    fn read_frames(
+        &self,
+        source: Source,
+        first: usize,
+        last: usize,
+    ) -> Result<Vec<Frame>, ReplayError> {
+        let rows = source.query_map(
+            &self.connection,
+            FRAMES,
+            &[self.timestamp(first), self.timestamp(last)],
+            |row| {
+                Ok((
+                    row.get::<_, i64>(0)?,
+                    row.get::<_, String>(1)?,
+                    row.get::<_, String>(2)?,
+                    row.get::<_, f64>(3)?,
+                ))
+            },
+        )?;
+
+        let mut frames = Vec::new();
+        let mut current: Option<(i64, FrameBuilder)> = None;
+        for (timestamp, metric, instance, value) in rows {
+            if current.as_ref().is_some_and(|(time, _)| *time != timestamp) {
+                if let Some((time, builder)) = current.take() {
+                    frames.push(self.finish(time, builder));
+                }
+            }
+            current
+                .get_or_insert_with(|| (timestamp, FrameBuilder::default()))
+                .1
+                .apply(&metric, instance, value);
+        }
+        if let Some((time, builder)) = current {
+            frames.push(self.finish(time, builder));
+        }
+        Ok(frames)
+    }
+
+    fn finish(&self, millis: i64, builder: FrameBuilder) -> Frame {
+        Frame {
+            timestamp: UNIX_EPOCH + Duration::from_millis(millis.max(0) as u64),
+            samples: builder.finish(self),
+        }
+    }
+}
+
+/// Collects the rows of one frame back into snapshot pieces. A frame only holds the
+/// collectors that ran at that time, so only sections with rows become samples.
+#[derive(Default)]
+struct FrameBuilder {
+    cpu_usage: Option<f32>,
+    cores: HashMap<String, CoreSnapshot>,
+    memory: Option<MemorySnapshot>,
+    gpus: BTreeMap<usize, HashMap<String, f64>>,
+    devices: BTreeMap<String, BlockDeviceSnapshot>,
+    interfaces: BTreeMap<String, InterfaceSnapshot>,
+    sensors: Vec<SensorReading>,
+}
+
+impl FrameBuilder {
+    /// Inverse of [`recorder::readings`].
+    fn apply(&mut self, metric: &str, instance: String, value: f64) {
+        match metric {
+            "cpu_usage_percent" if instance.is_empty() => self.cpu_usage = Some(value as f32),
+            "cpu_usage_percent" => self.core(instance).usage_percent = value as f32,
+            "cpu_frequency_mhz" => self.core(instance).frequency_mhz = value as u64,
+            "memory_total_bytes" => self.memory().total_bytes = value as u64,
+            "memory_used_bytes" => self.memory().used_bytes = value as u64,
+            "memory_free_bytes" => self.memory().free_bytes = value as u64,
+            "swap_total_bytes" => self.memory().swap_total_bytes = value as u64,
+            "swap_used_bytes" => self.memory().swap_used_bytes = value as u64,
+            "disk_read_bytes_per_second" => self.device(instance).read_bytes_per_sec = value as u64,
+            "disk_written_bytes_per_second" => {
+                self.device(instance).write_bytes_per_sec = value as u64
+            }
+            "network_received_bytes_per_second" => {
+                self.interface(instance).received_bytes_per_sec = value as u64
+            }
+            "network_transmitted_bytes_per_second" => {
+                self.interface(instance).transmitted_bytes_per_sec = value as u64
+            }
+            gpu if gpu.starts_with("gpu_") => {
+                if let Ok(index) = instance.parse() {
+                    self.gpus
+                        .entry(index)
+                        .or_default()
+                        .insert(gpu.to_string(), value);
+                }
+            }
+            sensor => {
+                let kind = SENSOR_KINDS
+                    .into_iter()
+                    .find(|kind| recorder::sensor_metric(*kind) == sensor);
+                if let Some(kind) = kind {
+                    self.sensors.push(SensorReading {
+                        label: instance,
+                        kind,
+                        value,
+                        min: None,
+                        max: None,
+                        critical: None,
+                    });
+                }
+            }
+        }
+    }
+
+    fn memory(&mut self) -> &mut MemorySnapshot {
+        self.memory.get_or_insert_with(MemorySnapshot::default)
+    }
+
+    fn core(&mut self, name: String) -> &mut CoreSnapshot {
+        self.cores
+            .entry(name.clone())
+            .or_insert_with(|| CoreSnapshot {
+                name,
+                ..CoreSnapshot::default()
+            })
+    }
+
+    fn device(&mut self, name: String) -> &mut BlockDeviceSnapshot {
+        self.devices
+            .entry(name.clone())
+            .or_insert_with(|| BlockDeviceSnapshot {
+                name,
+                ..BlockDeviceSnapshot::default()
+            })
+    }
+
+    fn interface(&mut self, name: String) -> &mut InterfaceSnapshot {
+        self.interfaces
+            .entry(name.clone())
+            .or_insert_with(|| InterfaceSnapshot {
+                name,
+                ..InterfaceSnapshot::default()
+            })
+    }
+
+    fn finish(self, recording: &Recording) -> Vec<Sample> {
+        let mut cores: Vec<CoreSnapshot> = self.cores.into_values().collect();
+        // Natural order, so cpu10 follows cpu9 rather than cpu1
+        cores.sort_by(|a, b| (a.name.len(), &a.name).cmp(&(b.name.len(), &b.name)));
+
+        // GPUs with neither VRAM figures nor telemetry never get rows; list them every frame
+        let gpus_without_rows = !recording.gpus.is_empty()
+            && recording
+                .gpus
+                .values()
+                .all(|gpu| gpu.vram.is_none() && gpu.telemetry.is_none());
+        let has_gpu = !self.gpus.is_empty() || gpus_without_rows;
+        let gpus = recording
+            .gpus
+            .iter()
+            .map(|(index, gpu)| {
+                let values = self.gpus.get(index);
+                let value = |metric: &str| values?.get(metric).copied();
+                let mut gpu = gpu.clone();
+                if let Some(vram) = &mut gpu.vram {
+                    vram.total_bytes =
+                        value("gpu_vram_total_bytes").map_or(vram.total_bytes, |v| v as u64);
+                    vram.used_bytes = value("gpu_vram_used_bytes").map(|v| v as u64);
+                }
+                // Only some telemetry is recorded; the rest stays unknown rather than stale
+                gpu.telemetry = gpu.telemetry.map(|_| GpuTelemetry {
+                    utilization_percent: value("gpu_utilization_percent").map(|v| v as u32),
+                    temperature_celsius: value("gpu_temperature_celsius").map(|v| v as u32),
+                    power_draw_milliwatts: value("gpu_power_milliwatts").map(|v| v as u32),
+                    ..GpuTelemetry::default()
+                });
+                gpu
+            })
+            .collect();
+
+        let mut samples = Vec::new();
+        if self.cpu_usage.is_some() || !cores.is_empty() {
+            samples.push(Sample::Cpu(CpuSnapshot {
+                brand: recording.cpu_brand.clone(),
+                vendor_id: recording.cpu_vendor_id.clone(),
+                usage_percent: self.cpu_usage.unwrap_or_default(),
+                cores,
+            }));
+        }
+        if let Some(memory) = self.memory {
+            samples.push(Sample::Memory(memory));
+        }
+        if has_gpu {
+            samples.push(Sample::Gpu(gpus));
+        }
+        if !self.devices.is_empty() {
+            samples.push(Sample::Storage(StorageSnapshot {
+                mounts: Vec::new(),
+                devices: self.devices.into_values().collect(),
+            }));
+        }
+        if !self.interfaces.is_empty() {
+            samples.push(Sample::Network(self.interfaces.into_values().collect()));
+        }
+        if !self.sensors.is_empty() {
+            samples.push(Sample::Sensors(self.sensors));
+        }
+        samples
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::recorder::{Recorder, Retention};
+
+    #[test]
+    fn frames_hold_only_the_sections_recorded_at_their_time() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("rec.db");
+        let day = Duration::from_secs(24 * 60 * 60);
+        let mut recorder = Recorder::open(
+            &path,
+            Retention {
+                raw: day,
+                minute: day,
+                hour: day,
+            },
+        )
+        .unwrap();
+
+        let cpu = Sample::Cpu(CpuSnapshot {
+            brand: "Test CPU".to_string(),
+            usage_percent: 25.0,
+            ..Default::default()
+        });
+        let memory = Sample::Memory(MemorySnapshot {
+            total_bytes: 1 << 30,
+            used_bytes: 1 << 29,
+            ..Default::default()
+        });
+        let start = SystemTime::now();
+        recorder
+            .record(start, &[cpu.clone(), memory.clone()])
+            .unwrap();
+        recorder
+            .record(start + Duration::from_secs(1), &[cpu])
+            .unwrap();
+
+        let recording = Recording::open(&path).unwrap();
+        assert_eq!(recording.len(), 2);
+        let frames = recording.frames(0, 1).unwrap();
+        let sections = |frame: &Frame| -> Vec<&'static str> {
+            frame
+                .samples
+                .iter()
+                .map(|sample| match sample {
+                    Sample::Cpu(_) => "cpu",
+                    Sample::Memory(_) => "memory",
+                    _ => "other",
+                })
+                .collect()
+        };
+        assert_eq!(sections(&frames[0]), ["cpu", "memory"]);
+        assert_eq!(sections(&frames[1]), ["cpu"]);
+        let Sample::Cpu(cpu) = &frames[1].samples[0] else {
+            unreachable!();
+        };
+        assert_eq!(cpu.brand, "Test CPU");
+        assert_eq!(cpu.usage_percent, 25.0);
+    }
+
+    #[test]
+    fn time_without_raw_samples_is_played_from_rollups() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("rec.db");
+        let day = Duration::from_secs(24 * 60 * 60);
+        let retention = Retention {
+            raw: day,
+            minute: day,
+            hour: day,
+        };
+        drop(Recorder::open(&path, retention).unwrap());
+
+        // The first hour survives only as an hour rollup, the start of the second as
+        // minute rollups, and the rest as raw samples
+        let hour = HOUR.as_millis() as i64;
+        let minute = MINUTE.as_millis() as i64;
+        let start = 1_699_999_200_000;
+        let connection = Connection::open(&path).unwrap();
+        let rollup = |resolution: i64, bucket: i64, avg: f64| {
+            connection
+                .execute(
+                    "INSERT INTO rollups VALUES (?1, ?2, 'cpu_usage_percent', '', 1, ?3, ?3, ?3)",
+                    rusqlite::params![resolution, start + bucket, avg],
+                )
+                .unwrap();
+        };
+        rollup(hour, 0, 5.0);
+        rollup(hour, hour, 99.0);
+        rollup(minute, hour, 10.0);
+        rollup(minute, hour + minute, 20.0);
+        for (offset, value) in [(90_000, 30.0), (91_000, 40.0)] {
+            connection
+                .execute(
+                    "INSERT INTO samples VALUES (?1, 'cpu_usage_percent', '', ?2)",
+                    rusqlite::params![start + hour + offset, value],
+                )
+                .unwrap();
+        }
+        drop(connection);
+
+        let recording = Recording::open(&path).unwrap();
+        let offsets: Vec<i64> = (0..recording.len())
+            .map(|index| recording.timestamp(index) - start)
+            .collect();
+        assert_eq!(
+            offsets,
+            [0, hour, hour + minute, hour + 90_000, hour + 91_000]
+        );
+
+        let usage: Vec<f32> = recording
+            .frames(0, recording.len() - 1)
+            .unwrap()
+            .iter()
+            .map(|frame| match &frame.samples[..] {
+                [Sample::Cpu(cpu)] => cpu.usage_percent,
+                _ => unreachable!(),
+            })
+            .collect();
+        assert_eq!(usage, [5.0, 10.0, 20.0, 30.0, 40.0]);
+
+        // A range inside one level reads only that level
+        assert_eq!(recording.frames(1, 2).unwrap().len(), 2);
+    }
+}
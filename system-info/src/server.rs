@@ -0,0 +1,103 @@
+use std::future::Future;
+use std::io;
+use std::net::SocketAddr;
+use std::sync::{Arc, Mutex};
+use std::time::Instant;
+
+use axum::extract::State;
+use axum::http::{header, HeaderMap, StatusCode};
+use axum::response::{IntoResponse, Response};
+use axum::routing::get;
+use axum::Router;
+use tokio::net::TcpListener;
+use tokio::{task, time};
+
+mod api;
+mod dashboard;
+mod stream;
+
+use crate::metrics::{self, Flavor};
+use crate::monitor::Monitor;
+
+pub type SharedMonitor = Arc<Mutex<Monitor>>;
+
+/// Keeps the monitor polled in the background and serves it over HTTP until Ctrl-C.
+/// With `dashboard`, the browser dashboard is served at `/` as well.
+pub async fn run(addr: SocketAddr, monitor: SharedMonitor, dashboard: bool) -> io::Result<()> {
+    tokio::spawn(poll(Arc::clone(&monitor)));
+    serve(addr, monitor, dashboard, ctrl_c()).await
+}
+
+/// Serves `/metrics` and the JSON API from a monitor that someone else keeps polling,
+/// until `shutdown` resolves.
+pub async fn serve(
+    addr: SocketAddr,
+    monitor: SharedMonitor,
+    dashboard: bool,
+    shutdown: impl Future<Output = ()> + Send + 'static,
+) -> io::Result<()> {
+    let listener = TcpListener::bind(addr).await?;
+    let local_addr = listener.local_addr()?;
+    if dashboard {
+        log::info!("Serving the dashboard on http://{}/", local_addr);
+    } else {
+        log::info!("Serving metrics and the JSON API on http://{}", local_addr);
+    }
+    axum::serve(listener, router(monitor, dashboard))
+        .with_graceful_shutdown(shutdown)
+        .await
+}
+
+fn router(monitor: SharedMonitor, dashboard: bool) -> Router {
+    let mut router = Router::new()
+        .route("/metrics", get(metrics_handler))
+        .merge(api::routes())
+        .merge(stream::routes());
+    if dashboard {
+        router = router.merge(dashboard::routes());
+    }
+    router.with_state(monitor)
+}
+
+/// Keeps the snapshot fresh by running whichever collectors are due.
+///
+/// Collectors read procfs and sysfs and call into NVML synchronously, so each round runs
+/// on the blocking pool instead of stalling a runtime worker that serves requests.
+async fn poll(monitor: SharedMonitor) {
+    loop {
+        let monitor = Arc::clone(&monitor);
+        let round = task::spawn_blocking(move || {
+            let mut monitor = monitor.lock().ok()?;
+            let now = Instant::now();
+            monitor.poll(now);
+            Some(monitor.next_due(now))
+        });
+        let Ok(Some(wait)) = round.await else {
+            break;
+        };
+        time::sleep(wait).await;
+    }
+}
+
+/// Only the standalone server listens for Ctrl-C; once tokio owns SIGINT the default
+/// handler no longer ends the process.
+async fn ctrl_c() {
+    if let Err(err) = tokio::signal::ctrl_c().await {
+        log::error!("Could not listen for Ctrl-C: {}", err);
+        std::future::pending::<()>().await;
+    }
+}
+
+/// Prometheus or OpenMetrics text, depending on what the scraper accepts.
+async fn metrics_handler(State(monitor): State<SharedMonitor>, headers: HeaderMap) -> Response {
+    let accept = headers
+        .get(header::ACCEPT)
+        .and_then(|value| value.to_str().ok());
+    let flavor = Flavor::from_accept(accept);
+
+    let Ok(monitor) = monitor.lock() else {
+        return StatusCode::INTERNAL_SERVER_ERROR.into_response();
+    };
+    let body = metrics::render(monitor.snapshot(), flavor);
+    ([(header::CONTENT_TYPE, flavor.content_type())], body).into_response()
+}
@@ -0,0 +1,340 @@
+use std::cmp::Reverse;
+use std::sync::MutexGuard;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use axum::extract::{Query, State};
+use axum::http::StatusCode;
+use axum::response::{IntoResponse, Response};
+use axum::routing::get;
+use axum::{Json, Router};
+use chrono::DateTime;
+use serde::{Deserialize, Serialize};
+
+use super::SharedMonitor;
+use crate::export::Document;
+use crate::history::{HistoryPoint, HistoryRange, Metric};
+use crate::monitor::Monitor;
+
+/// JSON endpoints under `/api`, documented in `docs/http-api.md`.
+pub fn routes() -> Router<SharedMonitor> {
+    Router::new()
+        .route("/api/snapshot", get(snapshot))
+        .route("/api/history", get(history))
+        .route("/api/processes", get(processes))
+        .route("/api/gpus", get(gpus))
+}
+
+/// An error reported to the client as `{"error": "..."}`.
+pub(super) struct ApiError {
+    status: StatusCode,
+    pub(super) message: String,
+}
+
+impl ApiError {
+    pub(super) fn bad_request(message: impl Into<String>) -> Self {
+        Self {
+            status: StatusCode::BAD_REQUEST,
+            message: message.into(),
+        }
+    }
+
+    pub(super) fn forbidden(message: impl Into<String>) -> Self {
+        Self {
+            status: StatusCode::FORBIDDEN,
+            message: message.into(),
+        }
+    }
+}
+
+impl IntoResponse for ApiError {
+    fn into_response(self) -> Response {
+        #[derive(Serialize)]
+        struct Body {
+            error: String,
+        }
+        (
+            self.status,
+            Json(Body {
+                error: self.message,
+            }),
+        )
+            .into_response()
+    }
+}
+
+pub(super) fn lock(monitor: &SharedMonitor) -> Result<MutexGuard<'_, Monitor>, ApiError> {
+    monitor.lock().map_err(|_| ApiError {
+        status: StatusCode::INTERNAL_SERVER_ERROR,
+        message: "monitor state is unavailable".to_string(),
+    })
+}
+
+/// The full snapshot, in the same versioned document as `--format json`.
+async fn snapshot(State(monitor): State<SharedMonitor>) -> Result<Response, ApiError> {
+    let monitor = lock(&monitor)?;
+    Ok(Json(Document::new(monitor.snapshot())).into_response())
+}
+
+async fn gpus(State(monitor): State<SharedMonitor>) -> Result<Response, ApiError> {
+    let monitor = lock(&monitor)?;
+    Ok(Json(&monitor.snapshot().gpus).into_response())
+}
+
+#[derive(Deserialize)]
+struct ProcessQuery {
+    /// `cpu` (default), `memory` or `pid`.
+    sort: Option<String>,
+    limit: Option<usize>,
+}
+
+/// Processes sorted busiest first, optionally truncated.
+async fn processes(
+    State(monitor): State<SharedMonitor>,
+    Query(query): Query<ProcessQuery>,
+) -> Result<Response, ApiError> {
+    let monitor = lock(&monitor)?;
+    let mut processes: Vec<_> = monitor.snapshot().processes.iter().collect();
+
+    match query.sort.as_deref().unwrap_or("cpu") {
+        "cpu" => processes.sort_by(|a, b| b.cpu_percent.total_cmp(&a.cpu_percent)),
+        "memory" => processes.sort_by_key(|process| Reverse(process.memory_bytes)),
+        "pid" => processes.sort_by_key(|process| process.pid),
+        other => {
+            return Err(ApiError::bad_request(format!(
+                "unknown sort key '{}'",
+                other
+            )))
+        }
+    }
+    processes.truncate(query.limit.unwrap_or(usize::MAX));
+
+    Ok(Json(processes).into_response())
+}
+
+#[derive(Deserialize)]
+struct HistoryQuery {
+    metric: String,
+    /// RFC 3339 timestamp or seconds since the Unix epoch.
+    since: Option<String>,
+    /// `raw` (default) for the last ten minutes, `minute` for 24 hours of one-minute averages.
+    resolution: Option<String>,
+}
+
+#[derive(Serialize)]
+struct HistoryResponse<'a> {
+    metric: &'static str,
+    unit: &'static str,
+    resolution: &'static str,
+    points: Vec<&'a HistoryPoint>,
+}
+
+async fn history(
+    State(monitor): State<SharedMonitor>,
+    Query(query): Query<HistoryQuery>,
+) -> Result<Response, ApiError> {
+    let metric = Metric::from_key(&query.metric).ok_or_else(|| {
+        let known: Vec<&str> = Metric::ALL.iter().map(|metric| metric.key()).collect();
+        ApiError::bad_request(format!(
+            "unknown metric '{}', expected one of {}",
+            query.metric,
+            known.join(", ")
+        ))
+    })?;
+    let (range, resolution) = match query.resolution.as_deref().unwrap_or("raw") {
+        "raw" => (HistoryRange::Recent, "raw"),
+        "minute" => (HistoryRange::LongTerm, "minute"),
+        other => {
+            return Err(ApiError::bad_request(format!(
+                "unknown resolution '{}', expected raw or minute",
+                other
+            )))
+        }
+    };
+    let since = query.since.as_deref().map(parse_since).transpose()?;
+
+    let monitor = lock(&monitor)?;
+    let points = monitor
+        .history()
+        .series(metric)
+        .points(range)
+        .iter()
+        .filter(|point| since.is_none_or(|since| point.timestamp >= since))
+        .collect();
+
+    Ok(Json(HistoryResponse {
+        metric: metric.key(),
+        unit: if metric.is_percentage() {
+            "percent"
+        } else {
+            "bytes_per_second"
+        },
+        resolution,
+        points,
+    })
+    .into_response())
+}
+
+fn parse_since(since: &str) -> Result<SystemTime, ApiError> {
+    if let Ok(seconds) = since.parse::<u64>() {
+        return UNIX_EPOCH
+            .checked_add(Duration::from_secs(seconds))
+            .ok_or_else(|| {
+                ApiError::bad_request(format!("'since' value '{}' is out of range", since))
+            });
+    }
+    DateTime::parse_from_rfc3339(since)
+        .map(SystemTime::from)
+        .map_err(|_| {
+            ApiError::bad_request(format!(
+                "invalid 'since' value '{}', expected RFC 3339 or Unix seconds",
+                since
+            ))
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{Arc, Mutex};
+
+    use axum::body::{self, Body};
+    use axum::http::Request;
+    use serde_json::Value;
+    use tower::ServiceExt;
+
+    use super::*;
+    use crate::collector::{CollectorRegistry, Sample};
+    use crate::snapshot::{CpuSnapshot, ProcessSnapshot};
+
+    /// Seconds since the epoch of the first CPU sample.
+    const START: u64 = 1_700_000_000;
+
+    /// A monitor with three processes and CPU usage of 10, 20 and 60 percent, recorded
+    /// at START, 30 s and 61 s later. The last sample closes the first one-minute bucket.
+    fn monitor() -> SharedMonitor {
+        let mut monitor = Monitor::new(CollectorRegistry::new());
+        for (offset, usage) in [(0, 10.0), (30, 20.0), (61, 60.0)] {
+            let cpu = Sample::Cpu(CpuSnapshot {
+                usage_percent: usage,
+                ..Default::default()
+            });
+            monitor.ingest_at(vec![cpu], UNIX_EPOCH + Duration::from_secs(START + offset));
+        }
+        let process = |pid, cpu_percent, memory_bytes| ProcessSnapshot {
+            pid,
+            cpu_percent,
+            memory_bytes,
+            ..Default::default()
+        };
+        monitor.ingest_at(
+            vec![Sample::Processes(vec![
+                process(3, 5.0, 300),
+                process(1, 50.0, 100),
+                process(2, 0.5, 900),
+            ])],
+            UNIX_EPOCH + Duration::from_secs(START + 62),
+        );
+        Arc::new(Mutex::new(monitor))
+    }
+
+    async fn get(uri: &str) -> (StatusCode, Value) {
+        let request = Request::get(uri).body(Body::empty()).unwrap();
+        let response = routes()
+            .with_state(monitor())
+            .oneshot(request)
+            .await
+            .unwrap();
+        let status = response.status();
+        let bytes = body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        (status, serde_json::from_slice(&bytes).unwrap())
+    }
+
+    /// Checks the `{"error": "..."}` shape promised by docs/http-api.md.
+    fn error_message(body: &Value) -> &str {
+        let object = body.as_object().expect("error body is an object");
+        assert_eq!(object.len(), 1, "unexpected fields in {}", body);
+        object["error"].as_str().expect("error is a string")
+    }
+
+    fn values(body: &Value) -> Vec<f64> {
+        body["points"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|point| point["value"].as_f64().unwrap())
+            .collect()
+    }
+
+    fn pids(body: &Value) -> Vec<u64> {
+        body.as_array()
+            .unwrap()
+            .iter()
+            .map(|process| process["pid"].as_u64().unwrap())
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn history_lists_raw_points_filtered_by_since() {
+        let (status, body) = get("/api/history?metric=cpu").await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["unit"], "percent");
+        assert_eq!(body["resolution"], "raw");
+        assert_eq!(values(&body), [10.0, 20.0, 60.0]);
+        assert_eq!(body["points"][0]["timestamp"], "2023-11-14T22:13:20.000Z");
+
+        let (_, body) = get(&format!("/api/history?metric=cpu&since={}", START + 30)).await;
+        assert_eq!(values(&body), [20.0, 60.0]);
+        let (_, body) = get("/api/history?metric=cpu&since=2023-11-14T22:14:00Z").await;
+        assert_eq!(values(&body), [60.0]);
+    }
+
+    #[tokio::test]
+    async fn history_at_minute_resolution_averages_closed_buckets() {
+        let (status, body) = get("/api/history?metric=cpu&resolution=minute").await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["resolution"], "minute");
+        assert_eq!(values(&body), [15.0]);
+
+        let (status, body) = get("/api/history?metric=cpu&resolution=hour").await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        assert!(error_message(&body).contains("'hour'"));
+    }
+
+    #[tokio::test]
+    async fn history_rejects_unknown_metrics_and_bad_since_values() {
+        let (status, body) = get("/api/history?metric=disk").await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        assert_eq!(
+            error_message(&body),
+            "unknown metric 'disk', expected one of cpu, ram, swap, gpu, \
+             network_received, network_transmitted"
+        );
+
+        let (status, body) = get("/api/history?metric=cpu&since=yesterday").await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        assert!(error_message(&body).contains("expected RFC 3339 or Unix seconds"));
+
+        let (status, body) = get(&format!("/api/history?metric=cpu&since={}", u64::MAX)).await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        assert!(error_message(&body).contains("out of range"));
+    }
+
+    #[tokio::test]
+    async fn processes_are_sorted_and_limited() {
+        let (status, body) = get("/api/processes").await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(pids(&body), [1, 3, 2]);
+
+        let (_, body) = get("/api/processes?sort=memory&limit=2").await;
+        assert_eq!(pids(&body), [2, 3]);
+        let (_, body) = get("/api/processes?sort=pid").await;
+        assert_eq!(pids(&body), [1, 2, 3]);
+        let (_, body) = get("/api/processes?limit=0").await;
+        assert!(pids(&body).is_empty());
+
+        let (status, body) = get("/api/processes?sort=name").await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        assert_eq!(error_message(&body), "unknown sort key 'name'");
+    }
+}
@@ -0,0 +1,33 @@
+use axum::http::header;
+use axum::response::{Html, IntoResponse};
+use axum::routing::get;
+use axum::Router;
+
+use super::SharedMonitor;
+
+// The dashboard is plain HTML/JS compiled into the binary, so `--web` needs no files on disk
+const INDEX_HTML: &str = include_str!("../../web/index.html");
+const DASHBOARD_JS: &str = include_str!("../../web/dashboard.js");
+const DASHBOARD_CSS: &str = include_str!("../../web/dashboard.css");
+
+/// The browser dashboard served by `--web`, fed from `/api/stream`.
+pub fn routes() -> Router<SharedMonitor> {
+    Router::new()
+        .route("/", get(|| async { Html(INDEX_HTML) }))
+        .route("/dashboard.js", get(script))
+        .route("/dashboard.css", get(stylesheet))
+}
+
+async fn script() -> impl IntoResponse {
+    (
+        [(header::CONTENT_TYPE, "text/javascript; charset=utf-8")],
+        DASHBOARD_JS,
+    )
+}
+
+async fn stylesheet() -> impl IntoResponse {
+    (
+        [(header::CONTENT_TYPE, "text/css; charset=utf-8")],
+        DASHBOARD_CSS,
+    )
+}
@@ -0,0 +1,199 @@
+use axum::extract::ws::{Message, WebSocket, WebSocketUpgrade};
+use axum::extract::{Query, State};
+use axum::http::{header, HeaderMap};
+use axum::response::Response;
+use axum::routing::get;
+use axum::Router;
+use serde::Deserialize;
+use serde_json::Value;
+
+use super::api::{lock, ApiError};
+use super::SharedMonitor;
+use crate::export::Document;
+use crate::snapshot::SystemSnapshot;
+
+/// Snapshot fields a client can subscribe to; `timestamp` is always sent.
+const SECTIONS: [&str; 7] = [
+    "cpu",
+    "memory",
+    "gpus",
+    "processes",
+    "storage",
+    "networks",
+    "sensors",
+];
+
+/// The live WebSocket feed at `/api/stream`, documented in `docs/http-api.md`.
+pub fn routes() -> Router<SharedMonitor> {
+    Router::new().route("/api/stream", get(upgrade))
+}
+
+#[derive(Deserialize)]
+struct StreamQuery {
+    /// Comma-separated sections, all of them when absent.
+    metrics: Option<String>,
+}
+
+/// A message from the client replacing its subscription.
+#[derive(Deserialize)]
+struct SubscribeRequest {
+    subscribe: Vec<String>,
+}
+
+async fn upgrade(
+    ws: WebSocketUpgrade,
+    State(monitor): State<SharedMonitor>,
+    Query(query): Query<StreamQuery>,
+    headers: HeaderMap,
+) -> Result<Response, ApiError> {
+    // Browsers let any page open a WebSocket to localhost, so only our own pages may
+    let origin = headers.get(header::ORIGIN).map(|value| value.to_str());
+    let host = headers
+        .get(header::HOST)
+        .and_then(|value| value.to_str().ok());
+    match origin {
+        None => {}
+        Some(Ok(origin)) if is_same_origin(origin, host) => {}
+        Some(origin) => {
+            return Err(ApiError::forbidden(format!(
+                "cross-origin WebSocket from '{}' refused",
+                origin.unwrap_or("?")
+            )))
+        }
+    }
+
+    let sections = match query.metrics {
+        Some(metrics) => parse_sections(metrics.split(','))?,
+        None => SECTIONS.to_vec(),
+    };
+    Ok(ws.on_upgrade(move |socket| stream(socket, monitor, sections)))
+}
+
+/// Whether an `Origin` header names the host the request was sent to.
+fn is_same_origin(origin: &str, host: Option<&str>) -> bool {
+    let Some(host) = host else {
+        return false;
+    };
+    origin
+        .strip_prefix("http://")
+        .or_else(|| origin.strip_prefix("https://"))
+        .is_some_and(|authority| authority.eq_ignore_ascii_case(host))
+}
+
+fn parse_sections<'a>(
+    names: impl IntoIterator<Item = &'a str>,
+) -> Result<Vec<&'static str>, ApiError> {
+    names
+        .into_iter()
+        .map(str::trim)
+        .filter(|name| !name.is_empty())
+        .map(|name| {
+            SECTIONS
+                .iter()
+                .find(|section| **section == name)
+                .copied()
+                .ok_or_else(|| {
+                    ApiError::bad_request(format!(
+                        "unknown metric '{}', expected one of {}",
+                        name,
+                        SECTIONS.join(", ")
+                    ))
+                })
+        })
+        .collect()
+}
+
+/// Sends a frame after every collection until the client goes away.
+async fn stream(mut socket: WebSocket, monitor: SharedMonitor, mut sections: Vec<&'static str>) {
+    let Ok(mut updates) = lock(&monitor).map(|monitor| monitor.subscribe()) else {
+        return;
+    };
+
+    // Start with the current state so clients do not wait a full interval for data
+    let mut send_frame = true;
+    loop {
+        if send_frame {
+            let Some(frame) = current_frame(&monitor, &sections) else {
+                break;
+            };
+            if socket.send(Message::Text(frame)).await.is_err() {
+                break;
+            }
+        }
+
+        // A slow client only ever sees the latest snapshot; intermediate ones are skipped
+        send_frame = tokio::select! {
+            changed = updates.changed() => {
+                if changed.is_err() {
+                    break; // Monitor is gone
+                }
+                true
+            }
+            message = socket.recv() => match message {
+                Some(Ok(Message::Text(text))) => match resubscribe(&text) {
+                    Ok(subscribed) => {
+                        sections = subscribed;
+                        true
+                    }
+                    Err(error) => {
+                        if socket.send(Message::Text(error)).await.is_err() {
+                            break;
+                        }
+                        false
+                    }
+                },
+                Some(Ok(Message::Close(_))) | Some(Err(_)) | None => break,
+                // Pings are answered by axum itself
+                Some(Ok(_)) => false,
+            },
+        };
+    }
+}
+
+/// Parses a `{"subscribe": [...]}` message, or describes what was wrong with it as an error frame.
+fn resubscribe(text: &str) -> Result<Vec<&'static str>, String> {
+    let request: SubscribeRequest = serde_json::from_str(text)
+        .map_err(|err| error_frame(&format!("invalid subscribe message: {}", err)))?;
+    parse_sections(request.subscribe.iter().map(String::as_str))
+        .map_err(|err| error_frame(&err.message))
+}
+
+fn error_frame(message: &str) -> String {
+    serde_json::json!({ "error": message }).to_string()
+}
+
+fn current_frame(monitor: &SharedMonitor, sections: &[&str]) -> Option<String> {
+    let monitor = lock(monitor).ok()?;
+    match frame(monitor.snapshot(), sections) {
+        Ok(frame) => Some(frame),
+        Err(err) => {
+            log::error!("Could not serialize snapshot for the stream: {}", err);
+            None
+        }
+    }
+}
+
+/// The versioned snapshot document, trimmed to the subscribed sections.
+fn frame(snapshot: &SystemSnapshot, sections: &[&str]) -> serde_json::Result<String> {
+    let mut document = serde_json::to_value(Document::new(snapshot))?;
+    if let Some(Value::Object(fields)) = document.get_mut("snapshot") {
+        fields.retain(|key, _| key == "timestamp" || sections.contains(&key.as_str()));
+    }
+    serde_json::to_string(&document)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn accepts_only_the_servers_own_origin() {
+        let host = Some("127.0.0.1:9100");
+        assert!(is_same_origin("http://127.0.0.1:9100", host));
+        assert!(is_same_origin("https://127.0.0.1:9100", host));
+        assert!(!is_same_origin("https://evil.example", host));
+        assert!(!is_same_origin("http://127.0.0.1:8080", host));
+        assert!(!is_same_origin("null", host));
+        assert!(!is_same_origin("http://127.0.0.1:9100", None));
+    }
+}
@@ -0,0 +1,638 @@
+use std::collections::{BTreeMap, VecDeque};
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, SystemTime};
+
+use serde::{Deserialize, Deserializer};
+use tokio::task::JoinHandle;
+use tokio::time::{self, MissedTickBehavior};
+
+use crate::monitor::Monitor;
+use crate::recorder::{self, Reading};
+
+mod protocol;
+mod transport;
+
+use transport::Transport;
+
+/// Wire format a sink writes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+enum Protocol {
+    Influx,
+    Graphite,
+    Statsd,
+}
+
+impl fmt::Display for Protocol {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Protocol::Influx => "InfluxDB",
+            Protocol::Graphite => "Graphite",
+            Protocol::Statsd => "StatsD",
+        })
+    }
+}
+
+/// One `[[sink]]` table of the sinks file.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct SinkConfig {
+    protocol: Protocol,
+    /// `http(s)://` for InfluxDB writes, `udp://HOST:PORT` for InfluxDB or StatsD,
+    /// `tcp://HOST:PORT` for Graphite.
+    url: String,
+    /// Sent as `Authorization: Token ...` with InfluxDB HTTP writes.
+    #[serde(default)]
+    token: Option<String>,
+    /// InfluxDB tags added to every line, e.g. the host name.
+    #[serde(default)]
+    tags: BTreeMap<String, String>,
+    /// Start of every Graphite and StatsD metric path.
+    #[serde(default = "default_prefix")]
+    prefix: String,
+    /// How often the current snapshot is taken.
+    #[serde(default = "default_interval", deserialize_with = "duration")]
+    interval: Duration,
+    /// How often buffered lines are sent.
+    #[serde(default = "default_flush_interval", deserialize_with = "duration")]
+    flush_interval: Duration,
+    /// Most lines per request or write. A full batch is sent without waiting for the flush.
+    #[serde(default = "default_batch_size")]
+    batch_size: usize,
+    /// Lines kept while the sink is unreachable, past which the oldest are dropped.
+    #[serde(default = "default_buffer_limit")]
+    buffer_limit: usize,
+    /// Extra attempts at a failed send before it waits for the next flush.
+    #[serde(default = "default_retries")]
+    retries: u32,
+    /// Wait before the first retry, doubled after each one.
+    #[serde(default = "default_retry_backoff", deserialize_with = "duration")]
+    retry_backoff: Duration,
+}
+
+fn default_prefix() -> String {
+    "system_info".to_string()
+}
+
+fn default_interval() -> Duration {
+    Duration::from_secs(10)
+}
+
+fn default_flush_interval() -> Duration {
+    Duration::from_secs(10)
+}
+
+fn default_batch_size() -> usize {
+    1000
+}
+
+fn default_buffer_limit() -> usize {
+    10_000
+}
+
+fn default_retries() -> u32 {
+    3
+}
+
+fn default_retry_backoff() -> Duration {
+    Duration::from_secs(1)
+}
+
+/// Reads durations written like the command line ones, e.g. `"10s"` or `"1m"`.
+fn duration<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Duration, D::Error> {
+    let text = String::deserialize(deserializer)?;
+    humantime::parse_duration(&text).map_err(serde::de::Error::custom)
+}
+
+#[derive(Debug, Deserialize)]
+struct SinksFile {
+    #[serde(default)]
+    sink: Vec<SinkConfig>,
+}
+
+/// Where a sink's lines go, taken apart from its URL.
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Endpoint {
+    Http(String),
+    Udp(String),
+    Tcp(String),
+}
+
+impl Endpoint {
+    fn parse(protocol: Protocol, url: &str) -> Result<Self, String> {
+        let Some((scheme, address)) = url.split_once("://") else {
+            return Err(format!("'{}' is not a URL", url));
+        };
+        let endpoint = match (protocol, scheme) {
+            (Protocol::Influx, "http" | "https") => return Ok(Endpoint::Http(url.to_string())),
+            (Protocol::Influx | Protocol::Statsd, "udp") => Endpoint::Udp(address.to_string()),
+            (Protocol::Graphite, "tcp") => Endpoint::Tcp(address.to_string()),
+            _ => {
+                return Err(format!(
+                    "{} cannot be sent to a {}:// URL",
+                    protocol, scheme
+                ))
+            }
+        };
+
+        let port = address
+            .rsplit_once(':')
+            .map(|(_, port)| port.parse::<u16>());
+        if !matches!(port, Some(Ok(_))) {
+            return Err(format!("'{}' needs a host and port", url));
+        }
+        Ok(endpoint)
+    }
+}
+
+impl fmt::Display for Endpoint {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Endpoint::Http(url) => f.write_str(url),
+            Endpoint::Udp(address) => write!(f, "udp://{}", address),
+            Endpoint::Tcp(address) => write!(f, "tcp://{}", address),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum SinkError {
+    Read(PathBuf, io::Error),
+    Parse(PathBuf, toml::de::Error),
+    /// A sink's settings don't fit together; holds its position in the file and why.
+    Invalid(PathBuf, usize, String),
+    /// The HTTP client for a sink could not be set up, e.g. because TLS failed to initialize.
+    Client(PathBuf, usize, reqwest::Error),
+}
+
+impl fmt::Display for SinkError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SinkError::Read(path, err) => {
+                write!(f, "could not read sinks file {}: {}", path.display(), err)
+            }
+            SinkError::Parse(path, err) => {
+                write!(f, "could not parse sinks file {}: {}", path.display(), err)
+            }
+            SinkError::Invalid(path, index, reason) => {
+                write!(f, "sink {} in {}: {}", index, path.display(), reason)
+            }
+            SinkError::Client(path, index, err) => write!(
+                f,
+                "sink {} in {}: could not create an HTTP client: {}",
+                index,
+                path.display(),
+                err
+            ),
+        }
+    }
+}
+
+impl std::error::Error for SinkError {}
+
+/// A configured sink, checked and ready to [`spawn`].
+pub struct Sink {
+    config: SinkConfig,
+    endpoint: Endpoint,
+    transport: Transport,
+}
+
+/// Reads the `[[sink]]` tables of a TOML file.
+pub fn load(path: &Path) -> Result<Vec<Sink>, SinkError> {
+    let text = fs::read_to_string(path).map_err(|err| SinkError::Read(path.to_path_buf(), err))?;
+    let file: SinksFile =
+        toml::from_str(&text).map_err(|err| SinkError::Parse(path.to_path_buf(), err))?;
+
+    file.sink
+        .into_iter()
+        .enumerate()
+        .map(|(index, config)| {
+            let invalid = |reason| SinkError::Invalid(path.to_path_buf(), index + 1, reason);
+            if config.interval.is_zero() || config.flush_interval.is_zero() {
+                return Err(invalid("intervals must be longer than zero".to_string()));
+            }
+            if config.batch_size == 0 || config.buffer_limit < config.batch_size {
+                return Err(invalid(
+                    "batch_size must be at least 1 and no more than buffer_limit".to_string(),
+                ));
+            }
+            let endpoint = Endpoint::parse(config.protocol, &config.url).map_err(invalid)?;
+            let transport = Transport::new(&endpoint, config.token.as_deref())
+                .map_err(|err| SinkError::Client(path.to_path_buf(), index + 1, err))?;
+            Ok(Sink {
+                config,
+                endpoint,
+                transport,
+            })
+        })
+        .collect()
+}
+
+/// Starts one background task per sink, each pushing `monitor`'s snapshots on its own schedule.
+pub fn spawn(sinks: Vec<Sink>, monitor: Arc<Mutex<Monitor>>) {
+    for sink in sinks {
+        log::info!("Pushing {} to {}", sink.config.protocol, sink.endpoint);
+        tokio::spawn(run(sink, Arc::clone(&monitor)));
+    }
+}
+
+/// Samples on its own schedule while a separate task does the sending, so a slow or
+/// dead endpoint delays nothing but its own lines.
+async fn run(sink: Sink, monitor: Arc<Mutex<Monitor>>) {
+    let Sink {
+        config,
+        endpoint,
+        transport,
+    } = sink;
+    let settings = Arc::new((config, endpoint));
+    let (config, endpoint) = &*settings;
+    let mut transport = Some(transport);
+    let mut sending: Option<JoinHandle<Sent>> = None;
+    let mut buffer = VecDeque::new();
+    let mut last_sample = None;
+
+    let mut samples = time::interval(config.interval);
+    let mut flushes = time::interval(config.flush_interval);
+    samples.set_missed_tick_behavior(MissedTickBehavior::Delay);
+    flushes.set_missed_tick_behavior(MissedTickBehavior::Delay);
+    // Both fire at once; skip that so the first sample follows a real collection
+    samples.tick().await;
+    flushes.tick().await;
+
+    loop {
+        let flush = tokio::select! {
+            _ = samples.tick() => {
+                let Some((readings, timestamp)) = sample(&monitor) else {
+                    break;
+                };
+                // Nothing new was collected since the last sample
+                if last_sample.replace(timestamp) == Some(timestamp) {
+                    continue;
+                }
+                let lines = protocol::encode(config, &readings, timestamp);
+                push_lines(&mut buffer, lines, config.buffer_limit, endpoint);
+                buffer.len() >= config.batch_size
+            }
+            _ = flushes.tick() => true,
+            sent = async { sending.as_mut().expect("guarded by the branch condition").await },
+                if sending.is_some() =>
+            {
+                sending = None;
+                let Ok(Sent { transport: returned, unsent }) = sent else {
+                    log::error!("Sending to {} panicked; stopping this sink", endpoint);
+                    break;
+                };
+                transport = Some(returned);
+                match unsent {
+                    // Keep going until the buffer is empty, as one flush always has
+                    None => !buffer.is_empty(),
+                    Some(batch) => {
+                        // Put the batch back in front, where its age says it belongs
+                        let newer = std::mem::replace(&mut buffer, batch.into());
+                        push_lines(&mut buffer, newer, config.buffer_limit, endpoint);
+                        log::warn!(
+                            "Could not push to {}, keeping {} lines for the next flush",
+                            endpoint,
+                            buffer.len()
+                        );
+                        false
+                    }
+                }
+            }
+        };
+
+        if !flush || buffer.is_empty() {
+            continue;
+        }
+        // Only one send at a time; the busy one picks the rest up when it is done
+        if let Some(idle) = transport.take() {
+            let count = buffer.len().min(config.batch_size);
+            let batch = buffer.drain(..count).collect();
+            sending = Some(tokio::spawn(send(Arc::clone(&settings), idle, batch)));
+        }
+    }
+}
+
+/// Appends `lines`, dropping the oldest buffered ones past `limit`.
+fn push_lines(
+    buffer: &mut VecDeque<String>,
+    lines: impl IntoIterator<Item = String>,
+    limit: usize,
+    endpoint: &Endpoint,
+) {
+    buffer.extend(lines);
+    let excess = buffer.len().saturating_sub(limit);
+    if excess > 0 {
+        buffer.drain(..excess);
+        log::warn!(
+            "Dropped {} lines that could not be pushed to {}",
+            excess,
+            endpoint
+        );
+    }
+}
+
+/// Readings of the monitor's latest snapshot, or `None` once the monitor is gone.
+fn sample(monitor: &Mutex<Monitor>) -> Option<(Vec<Reading>, SystemTime)> {
+    let monitor = monitor.lock().ok()?;
+    let snapshot = monitor.snapshot();
+    Some((recorder::readings(snapshot), snapshot.timestamp))
+}
+
+/// What a finished [`send`] hands back to the sink's loop.
+struct Sent {
+    transport: Transport,
+    /// The batch, if it still failed after every retry.
+    unsent: Option<Vec<String>>,
+}
+
+/// Sends one batch, retrying with backoff.
+async fn send(
+    settings: Arc<(SinkConfig, Endpoint)>,
+    mut transport: Transport,
+    batch: Vec<String>,
+) -> Sent {
+    let (config, endpoint) = &*settings;
+    let mut backoff = config.retry_backoff;
+    let mut attempt = 0;
+    while let Err(err) = transport.send(&batch).await {
+        if attempt == config.retries {
+            log::debug!("Giving up on this flush to {}: {}", endpoint, err);
+            return Sent {
+                transport,
+                unsent: Some(batch),
+            };
+        }
+        log::debug!(
+            "Push to {} failed, retrying in {:?}: {}",
+            endpoint,
+            backoff,
+            err
+        );
+        time::sleep(backoff).await;
+        backoff *= 2;
+        attempt += 1;
+    }
+    Sent {
+        transport,
+        unsent: None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::net::SocketAddr;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::time::UNIX_EPOCH;
+
+    use axum::extract::State;
+    use axum::http::{HeaderMap, StatusCode};
+    use axum::routing::post;
+    use axum::Router;
+    use tokio::io::AsyncReadExt;
+    use tokio::net::{TcpListener, UdpSocket};
+
+    use super::*;
+
+    /// A sink as `load` would build it from one `[[sink]]` table.
+    fn sink(table: &str) -> Sink {
+        let config: SinkConfig = toml::from_str(table).unwrap();
+        let endpoint = Endpoint::parse(config.protocol, &config.url).unwrap();
+        let transport = Transport::new(&endpoint, config.token.as_deref()).unwrap();
+        Sink {
+            config,
+            endpoint,
+            transport,
+        }
+    }
+
+    fn readings() -> Vec<Reading> {
+        vec![
+            Reading {
+                metric: "cpu_usage_percent",
+                instance: String::new(),
+                value: 12.5,
+            },
+            Reading {
+                metric: "gpu_temperature_celsius",
+                instance: "GeForce RTX 3080".to_string(),
+                value: 61.0,
+            },
+        ]
+    }
+
+    fn timestamp() -> SystemTime {
+        UNIX_EPOCH + Duration::from_secs(1_700_000_000)
+    }
+
+    /// Encodes the test readings for `sink` and sends them once.
+    async fn push(sink: &mut Sink) {
+        let lines = protocol::encode(&sink.config, &readings(), timestamp());
+        sink.transport.send(&lines).await.unwrap();
+    }
+
+    /// The `Authorization` header and body of one write request.
+    type Request = (Option<String>, String);
+
+    /// Records request bodies and answers with `failures` server errors before succeeding.
+    #[derive(Clone, Default)]
+    struct InfluxServer {
+        requests: Arc<Mutex<Vec<Request>>>,
+        failures: Arc<AtomicUsize>,
+    }
+
+    async fn write(
+        State(server): State<InfluxServer>,
+        headers: HeaderMap,
+        body: String,
+    ) -> StatusCode {
+        let token = headers
+            .get("authorization")
+            .and_then(|value| value.to_str().ok())
+            .map(str::to_string);
+        server.requests.lock().unwrap().push((token, body));
+        let failed = server
+            .failures
+            .fetch_update(Ordering::SeqCst, Ordering::SeqCst, |left| {
+                left.checked_sub(1)
+            });
+        if failed.is_ok() {
+            StatusCode::INTERNAL_SERVER_ERROR
+        } else {
+            StatusCode::NO_CONTENT
+        }
+    }
+
+    async fn influx_server(failures: usize) -> (SocketAddr, InfluxServer) {
+        let server = InfluxServer::default();
+        server.failures.store(failures, Ordering::SeqCst);
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let router = Router::new()
+            .route("/api/v2/write", post(write))
+            .with_state(server.clone());
+        tokio::spawn(async move { axum::serve(listener, router).await });
+        (addr, server)
+    }
+
+    #[tokio::test]
+    async fn influx_over_http_sends_tagged_lines_with_the_token() {
+        let (addr, server) = influx_server(0).await;
+        let mut sink = sink(&format!(
+            r#"
+            protocol = "influx"
+            url = "http://{}/api/v2/write?org=qa&bucket=soak"
+            token = "secret"
+            tags = {{ host = "web1" }}
+            "#,
+            addr
+        ));
+        push(&mut sink).await;
+
+        let requests = server.requests.lock().unwrap();
+        assert_eq!(
+            *requests,
+            [(
+                Some("Token secret".to_string()),
+                "cpu_usage_percent,host=web1 value=12.5 1700000000000000000\n\
+                 gpu_temperature_celsius,host=web1,instance=GeForce\\ RTX\\ 3080 value=61 1700000000000000000\n"
+                    .to_string()
+            )]
+        );
+    }
+
+    #[tokio::test]
+    async fn influx_over_udp_sends_lines_in_a_datagram() {
+        let listener = UdpSocket::bind("127.0.0.1:0").await.unwrap();
+        let mut sink = sink(&format!(
+            r#"
+            protocol = "influx"
+            url = "udp://{}"
+            "#,
+            listener.local_addr().unwrap()
+        ));
+        push(&mut sink).await;
+
+        let mut datagram = [0; 2048];
+        let len = listener.recv(&mut datagram).await.unwrap();
+        assert_eq!(
+            std::str::from_utf8(&datagram[..len]).unwrap(),
+            "cpu_usage_percent value=12.5 1700000000000000000\n\
+             gpu_temperature_celsius,instance=GeForce\\ RTX\\ 3080 value=61 1700000000000000000\n"
+        );
+    }
+
+    #[tokio::test]
+    async fn graphite_over_tcp_sends_plaintext_paths() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let mut sink = sink(&format!(
+            r#"
+            protocol = "graphite"
+            url = "tcp://{}"
+            prefix = "servers.web1"
+            "#,
+            listener.local_addr().unwrap()
+        ));
+        let accept = tokio::spawn(async move {
+            let (mut stream, _) = listener.accept().await.unwrap();
+            let mut received = String::new();
+            stream.read_to_string(&mut received).await.unwrap();
+            received
+        });
+        push(&mut sink).await;
+        drop(sink);
+
+        assert_eq!(
+            accept.await.unwrap(),
+            "servers.web1.cpu_usage_percent 12.5 1700000000\n\
+             servers.web1.gpu_temperature_celsius.GeForce_RTX_3080 61 1700000000\n"
+        );
+    }
+
+    #[tokio::test]
+    async fn statsd_over_udp_sends_gauges_and_resets_negative_ones() {
+        let listener = UdpSocket::bind("127.0.0.1:0").await.unwrap();
+        let mut sink = sink(&format!(
+            r#"
+            protocol = "statsd"
+            url = "udp://{}"
+            "#,
+            listener.local_addr().unwrap()
+        ));
+        let mut readings = readings();
+        readings[1].value = -3.0;
+        let lines = protocol::encode(&sink.config, &readings, timestamp());
+        sink.transport.send(&lines).await.unwrap();
+
+        let mut datagram = [0; 2048];
+        let len = listener.recv(&mut datagram).await.unwrap();
+        assert_eq!(
+            std::str::from_utf8(&datagram[..len]).unwrap(),
+            "system_info.cpu_usage_percent:12.5|g\n\
+             system_info.gpu_temperature_celsius.GeForce_RTX_3080:0|g\n\
+             system_info.gpu_temperature_celsius.GeForce_RTX_3080:-3|g\n"
+        );
+    }
+
+    #[tokio::test]
+    async fn failed_sends_are_retried_until_one_succeeds() {
+        let (addr, server) = influx_server(2).await;
+        let Sink {
+            config,
+            endpoint,
+            transport,
+        } = sink(&format!(
+            r#"
+            protocol = "influx"
+            url = "http://{}/api/v2/write"
+            retries = 3
+            retry_backoff = "1ms"
+            "#,
+            addr
+        ));
+        let batch = vec!["a value=1 1".to_string()];
+        let sent = send(Arc::new((config, endpoint)), transport, batch).await;
+
+        assert!(sent.unsent.is_none());
+        assert_eq!(server.requests.lock().unwrap().len(), 3);
+    }
+
+    #[tokio::test]
+    async fn a_batch_that_keeps_failing_is_handed_back() {
+        let (addr, server) = influx_server(usize::MAX).await;
+        let Sink {
+            config,
+            endpoint,
+            transport,
+        } = sink(&format!(
+            r#"
+            protocol = "influx"
+            url = "http://{}/api/v2/write"
+            retries = 1
+            retry_backoff = "1ms"
+            "#,
+            addr
+        ));
+        let batch = vec!["a value=1 1".to_string(), "b value=2 1".to_string()];
+        let sent = send(Arc::new((config, endpoint)), transport, batch.clone()).await;
+
+        assert_eq!(sent.unsent, Some(batch));
+        assert_eq!(server.requests.lock().unwrap().len(), 2);
+    }
+
+    #[test]
+    fn a_full_buffer_drops_the_oldest_lines() {
+        let endpoint = Endpoint::Udp("127.0.0.1:8125".to_string());
+        let mut buffer = VecDeque::new();
+        let lines = |range: std::ops::Range<u32>| range.map(|n| n.to_string());
+
+        push_lines(&mut buffer, lines(0..3), 4, &endpoint);
+        assert_eq!(buffer, ["0", "1", "2"]);
+        push_lines(&mut buffer, lines(3..6), 4, &endpoint);
+        assert_eq!(buffer, ["2", "3", "4", "5"]);
+    }
+}
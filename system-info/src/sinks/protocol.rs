@@ -0,0 +1,92 @@
+use std::collections::BTreeMap;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use super::{Protocol, SinkConfig};
+use crate::recorder::Reading;
+
+/// Lines for one snapshot in the sink's protocol, without line endings.
+pub fn encode(config: &SinkConfig, readings: &[Reading], timestamp: SystemTime) -> Vec<String> {
+    let since_epoch = timestamp.duration_since(UNIX_EPOCH).unwrap_or_default();
+    // None of the protocols can carry NaN or infinity
+    let readings = readings.iter().filter(|reading| reading.value.is_finite());
+
+    match config.protocol {
+        Protocol::Influx => readings
+            .map(|reading| influx_line(reading, &config.tags, since_epoch.as_nanos()))
+            .collect(),
+        Protocol::Graphite => readings
+            .map(|reading| {
+                format!(
+                    "{} {} {}",
+                    metric_path(&config.prefix, reading),
+                    reading.value,
+                    since_epoch.as_secs()
+                )
+            })
+            .collect(),
+        Protocol::Statsd => readings
+            .flat_map(|reading| {
+                let path = metric_path(&config.prefix, reading);
+                // A signed gauge is read as a change, so negative values are set from zero
+                let reset = (reading.value < 0.0).then(|| format!("{}:0|g", path));
+                reset
+                    .into_iter()
+                    .chain([format!("{}:{}|g", path, reading.value)])
+            })
+            .collect(),
+    }
+}
+
+/// `metric,instance=...,tag=... value=... timestamp`, one measurement per metric.
+fn influx_line(reading: &Reading, tags: &BTreeMap<String, String>, nanos: u128) -> String {
+    let mut line = escape_influx(reading.metric, &[',', ' ']);
+    let instance =
+        (!reading.instance.is_empty()).then_some(("instance", reading.instance.as_str()));
+    let mut tags: Vec<(&str, &str)> = tags
+        .iter()
+        .map(|(key, value)| (key.as_str(), value.as_str()))
+        .chain(instance)
+        .collect();
+    // InfluxDB writes fastest with tags in key order
+    tags.sort_unstable();
+
+    for (key, value) in tags {
+        line.push(',');
+        line.push_str(&escape_influx(key, &[',', '=', ' ']));
+        line.push('=');
+        line.push_str(&escape_influx(value, &[',', '=', ' ']));
+    }
+    format!("{} value={} {}", line, reading.value, nanos)
+}
+
+fn escape_influx(text: &str, special: &[char]) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        if special.contains(&c) || c == '\\' {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+    }
+    escaped
+}
+
+/// `prefix.metric.instance`, with anything that would split or break the path replaced.
+fn metric_path(prefix: &str, reading: &Reading) -> String {
+    let mut path = prefix.to_string();
+    for part in [reading.metric, reading.instance.as_str()] {
+        if part.is_empty() {
+            continue;
+        }
+        if !path.is_empty() {
+            path.push('.');
+        }
+        path.extend(part.chars().map(|c| {
+            if c.is_ascii_alphanumeric() || c == '-' || c == '_' {
+                c
+            } else {
+                '_'
+            }
+        }));
+    }
+    path
+}
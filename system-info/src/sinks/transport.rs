@@ -0,0 +1,208 @@
+use std::fmt;
+use std::io;
+use std::net::{Ipv4Addr, Ipv6Addr, SocketAddr};
+use std::time::Duration;
+
+use tokio::io::AsyncWriteExt;
+use tokio::net::{self, TcpStream, UdpSocket};
+use tokio::time;
+
+use super::Endpoint;
+
+/// Longest wait for a connection or an HTTP response.
+const TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Largest UDP payload sent, so datagrams fit an Ethernet frame without fragmenting.
+const MAX_DATAGRAM: usize = 1432;
+
+#[derive(Debug)]
+pub enum SendError {
+    Io(io::Error),
+    Http(reqwest::Error),
+    /// The server answered with an error status; holds the status and response body.
+    Status(reqwest::StatusCode, String),
+}
+
+impl fmt::Display for SendError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SendError::Io(err) => err.fmt(f),
+            SendError::Http(err) => err.fmt(f),
+            SendError::Status(status, body) => write!(f, "{}: {}", status, body.trim()),
+        }
+    }
+}
+
+impl From<io::Error> for SendError {
+    fn from(err: io::Error) -> Self {
+        SendError::Io(err)
+    }
+}
+
+impl From<reqwest::Error> for SendError {
+    fn from(err: reqwest::Error) -> Self {
+        SendError::Http(err)
+    }
+}
+
+/// A connection to one endpoint. Sockets are opened on first use and again after a
+/// failure, so a sink that starts before its server catches up once it appears.
+pub enum Transport {
+    Http {
+        client: reqwest::Client,
+        url: String,
+        token: Option<String>,
+    },
+    Udp {
+        address: String,
+        socket: Option<UdpSocket>,
+    },
+    Tcp {
+        address: String,
+        stream: Option<TcpStream>,
+    },
+}
+
+impl Transport {
+    pub fn new(endpoint: &Endpoint, token: Option<&str>) -> Result<Self, reqwest::Error> {
+        Ok(match endpoint {
+            Endpoint::Http(url) => Transport::Http {
+                client: reqwest::Client::builder().timeout(TIMEOUT).build()?,
+                url: url.clone(),
+                token: token.map(str::to_string),
+            },
+            Endpoint::Udp(address) => Transport::Udp {
+                address: address.clone(),
+                socket: None,
+            },
+            Endpoint::Tcp(address) => Transport::Tcp {
+                address: address.clone(),
+                stream: None,
+            },
+        })
+    }
+
+    /// Delivers the lines, newline-terminated: one HTTP request, as few datagrams as
+    /// fit, or one write to the stream.
+    pub async fn send(&mut self, lines: &[String]) -> Result<(), SendError> {
+        match self {
+            Transport::Http { client, url, token } => {
+                let mut request = client.post(url.as_str()).body(join(lines));
+                if let Some(token) = token {
+                    request = request.header("Authorization", format!("Token {}", token));
+                }
+                let response = request.send().await?;
+                let status = response.status();
+                if !status.is_success() {
+                    return Err(SendError::Status(status, response.text().await?));
+                }
+                Ok(())
+            }
+            Transport::Udp { address, socket } => {
+                let connected = match socket {
+                    Some(connected) => connected,
+                    None => socket.insert(connect_udp(address).await?),
+                };
+                for datagram in datagrams(lines) {
+                    if let Err(err) = connected.send(datagram.as_bytes()).await {
+                        *socket = None;
+                        return Err(err.into());
+                    }
+                }
+                Ok(())
+            }
+            Transport::Tcp { address, stream } => {
+                let connected = match stream {
+                    Some(connected) => connected,
+                    None => {
+                        let connect = TcpStream::connect(address.as_str());
+                        let connected = time::timeout(TIMEOUT, connect)
+                            .await
+                            .map_err(|_| io::Error::from(io::ErrorKind::TimedOut))??;
+                        stream.insert(connected)
+                    }
+                };
+                if let Err(err) = connected.write_all(join(lines).as_bytes()).await {
+                    *stream = None;
+                    return Err(err.into());
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+/// A socket bound on the same address family as `address` and connected to it.
+async fn connect_udp(address: &str) -> io::Result<UdpSocket> {
+    let target = net::lookup_host(address)
+        .await?
+        .next()
+        .ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, "no address found"))?;
+    let local = match target {
+        SocketAddr::V4(_) => SocketAddr::from((Ipv4Addr::UNSPECIFIED, 0)),
+        SocketAddr::V6(_) => SocketAddr::from((Ipv6Addr::UNSPECIFIED, 0)),
+    };
+    let socket = UdpSocket::bind(local).await?;
+    socket.connect(target).await?;
+    Ok(socket)
+}
+
+fn join(lines: &[String]) -> String {
+    lines.iter().map(|line| format!("{}\n", line)).collect()
+}
+
+/// Packs whole lines into payloads of at most [`MAX_DATAGRAM`] bytes. A longer line
+/// goes out on its own.
+fn datagrams(lines: &[String]) -> Vec<String> {
+    let mut datagrams = Vec::new();
+    let mut current = String::new();
+    for line in lines {
+        if !current.is_empty() && current.len() + line.len() + 1 > MAX_DATAGRAM {
+            datagrams.push(std::mem::take(&mut current));
+        }
+        current.push_str(line);
+        current.push('\n');
+    }
+    if !current.is_empty() {
+        datagrams.push(current);
+    }
+    datagrams
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn datagrams_hold_whole_lines_up_to_the_size_limit() {
+        // 100-byte lines, 101 bytes with the newline: 14 fit in 1432 bytes
+        let lines: Vec<String> = (0..30).map(|n| format!("{:0100}", n)).collect();
+        let packed = datagrams(&lines);
+
+        assert_eq!(
+            packed.iter().map(String::len).collect::<Vec<_>>(),
+            [1414, 1414, 202]
+        );
+        assert!(packed.iter().all(|datagram| datagram.ends_with('\n')));
+        assert_eq!(packed.concat(), join(&lines));
+    }
+
+    #[test]
+    fn a_line_longer_than_a_datagram_goes_out_alone() {
+        let lines = vec!["short".to_string(), "x".repeat(2000), "tail".to_string()];
+        let packed = datagrams(&lines);
+
+        assert_eq!(packed.len(), 3);
+        assert_eq!(packed[1].len(), 2001);
+    }
+
+    #[test]
+    fn a_datagram_fills_to_exactly_the_limit() {
+        // Two lines of 715 bytes plus newlines make 1432 bytes
+        let lines = vec!["a".repeat(715), "b".repeat(715), "c".to_string()];
+        let packed = datagrams(&lines);
+
+        assert_eq!(packed[0].len(), MAX_DATAGRAM);
+        assert_eq!(packed[1], "c\n");
+    }
+}
@@ -0,0 +1,413 @@
+use std::time::SystemTime;
+
+use serde::{Deserialize, Serialize};
+
+use crate::collector::Sample;
+
+/// A point-in-time view of the machine, produced by the collectors and consumed by the UI.
+#[derive(Debug, Clone, Serialize)]
+pub struct SystemSnapshot {
+    #[serde(serialize_with = "crate::export::serialize_timestamp")]
+    pub timestamp: SystemTime,
+    pub cpu: CpuSnapshot,
+    pub memory: MemorySnapshot,
+    pub gpus: Vec<GpuSnapshot>,
+    pub processes: Vec<ProcessSnapshot>,
+    pub storage: StorageSnapshot,
+    pub networks: Vec<InterfaceSnapshot>,
+    pub sensors: Vec<SensorReading>,
+}
+
+/// CPU identity plus load, both averaged and per logical core.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct CpuSnapshot {
+    pub brand: String,
+    pub vendor_id: String,
+    /// Average usage across all logical cores.
+    pub usage_percent: f32,
+    pub cores: Vec<CoreSnapshot>,
+}
+
+/// Load and clock of a single logical core.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct CoreSnapshot {
+    pub name: String,
+    pub usage_percent: f32,
+    pub frequency_mhz: u64,
+}
+
+/// Physical memory and swap figures, in bytes.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct MemorySnapshot {
+    pub total_bytes: u64,
+    pub used_bytes: u64,
+    pub free_bytes: u64,
+    pub swap_total_bytes: u64,
+    pub swap_used_bytes: u64,
+    /// Kernel accounting from `/proc/meminfo`, only available on Linux.
+    pub breakdown: Option<MemoryBreakdown>,
+}
+
+/// Where physical memory goes, as reported by `/proc/meminfo`. All sizes are in bytes.
+///
+/// The figures overlap: `shared_bytes` is part of `cached_bytes`, and `dirty_bytes`
+/// and `writeback_bytes` are page cache waiting to reach disk.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct MemoryBreakdown {
+    /// Estimate of memory that can be handed to applications without swapping.
+    pub available_bytes: u64,
+    pub buffers_bytes: u64,
+    /// Page cache, including shared memory.
+    pub cached_bytes: u64,
+    /// tmpfs and shared memory segments.
+    pub shared_bytes: u64,
+    /// Kernel slab allocations.
+    pub slab_bytes: u64,
+    pub dirty_bytes: u64,
+    pub writeback_bytes: u64,
+    pub huge_pages_total: u64,
+    pub huge_pages_free: u64,
+    pub huge_page_size_bytes: u64,
+}
+
+impl MemoryBreakdown {
+    /// Memory reserved for the huge page pool, whether or not it is in use.
+    pub fn huge_pages_bytes(&self) -> u64 {
+        self.huge_pages_total * self.huge_page_size_bytes
+    }
+}
+
+impl MemorySnapshot {
+    /// Share of physical memory in use.
+    pub fn used_percent(&self) -> f32 {
+        percent(self.used_bytes, self.total_bytes)
+    }
+
+    /// Share of swap in use, or `None` when the machine has no swap.
+    pub fn swap_used_percent(&self) -> Option<f32> {
+        (self.swap_total_bytes > 0).then(|| percent(self.swap_used_bytes, self.swap_total_bytes))
+    }
+}
+
+/// One running process.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct ProcessSnapshot {
+    pub pid: u32,
+    pub parent_pid: Option<u32>,
+    pub name: String,
+    /// Owning user name, empty if it could not be resolved.
+    pub user: String,
+    /// Usage relative to a single core, so it can exceed 100% for multithreaded processes.
+    pub cpu_percent: f32,
+    pub memory_bytes: u64,
+    pub disk_read_bytes_per_sec: u64,
+    pub disk_write_bytes_per_sec: u64,
+    pub status: String,
+    /// Start time in seconds since the Unix epoch.
+    pub start_time: u64,
+    pub command: String,
+}
+
+impl ProcessSnapshot {
+    /// Case-insensitive match against name, user, command line and PID.
+    /// `filter` must already be lowercase; an empty filter matches everything.
+    pub fn matches_filter(&self, filter: &str) -> bool {
+        filter.is_empty()
+            || self.name.to_lowercase().contains(filter)
+            || self.user.to_lowercase().contains(filter)
+            || self.command.to_lowercase().contains(filter)
+            || self.pid.to_string().contains(filter)
+    }
+}
+
+/// Mounted filesystems together with I/O rates of the block devices behind them.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct StorageSnapshot {
+    pub mounts: Vec<MountSnapshot>,
+    pub devices: Vec<BlockDeviceSnapshot>,
+}
+
+/// One mounted filesystem.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct MountSnapshot {
+    /// Device or source the filesystem was mounted from, e.g. `/dev/nvme0n1p2`.
+    pub device: String,
+    pub mount_point: String,
+    pub file_system: String,
+    pub total_bytes: u64,
+    pub available_bytes: u64,
+    pub is_removable: bool,
+}
+
+impl MountSnapshot {
+    /// Share of the filesystem in use, counting space reserved for root as used.
+    pub fn used_percent(&self) -> f32 {
+        percent(
+            self.total_bytes.saturating_sub(self.available_bytes),
+            self.total_bytes,
+        )
+    }
+}
+
+/// Throughput of a whole block device. Rates are averaged since the previous sample;
+/// totals count from boot.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct BlockDeviceSnapshot {
+    /// Kernel name, e.g. `sda` or `nvme0n1`.
+    pub name: String,
+    pub read_bytes_per_sec: u64,
+    pub write_bytes_per_sec: u64,
+    pub read_iops: f32,
+    pub write_iops: f32,
+    pub total_read_bytes: u64,
+    pub total_written_bytes: u64,
+    pub total_reads: u64,
+    pub total_writes: u64,
+}
+
+/// One network interface. Rates are averaged since the previous sample; counters are
+/// totals since the interface came up.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct InterfaceSnapshot {
+    pub name: String,
+    pub mac_address: String,
+    /// IPv4 and IPv6 addresses in textual form.
+    pub addresses: Vec<String>,
+    pub received_bytes_per_sec: u64,
+    pub transmitted_bytes_per_sec: u64,
+    pub received_packets_per_sec: f32,
+    pub transmitted_packets_per_sec: f32,
+    pub total_received_bytes: u64,
+    pub total_transmitted_bytes: u64,
+    pub receive_errors: u64,
+    pub transmit_errors: u64,
+    /// Dropped packets, only reported on Linux.
+    pub receive_drops: Option<u64>,
+    pub transmit_drops: Option<u64>,
+}
+
+impl InterfaceSnapshot {
+    /// Loopback traffic never leaves the machine, so it is left out of totals.
+    pub fn is_loopback(&self) -> bool {
+        self.name
+            .strip_prefix("lo")
+            .is_some_and(|unit| unit.chars().all(|c| c.is_ascii_digit()))
+    }
+}
+
+/// One hardware sensor channel, in the unit given by its [`SensorKind`].
+#[derive(Debug, Clone, Serialize)]
+pub struct SensorReading {
+    /// Chip and channel, e.g. `coretemp Package id 0` or `nct6798 fan2`.
+    pub label: String,
+    pub kind: SensorKind,
+    pub value: f64,
+    /// Lowest safe value, reported for fans and voltages.
+    pub min: Option<f64>,
+    /// Highest value the hardware expects in normal operation.
+    pub max: Option<f64>,
+    /// Value at which the hardware considers the reading critical.
+    pub critical: Option<f64>,
+}
+
+impl SensorReading {
+    /// Whether the reading has reached its critical limit or dropped below its minimum.
+    pub fn is_critical(&self) -> bool {
+        self.critical.is_some_and(|critical| self.value >= critical)
+            || self.min.is_some_and(|min| self.value < min)
+    }
+
+    /// Whether the reading is above its normal maximum.
+    pub fn is_above_max(&self) -> bool {
+        self.max.is_some_and(|max| self.value > max)
+    }
+}
+
+/// What a [`SensorReading`] measures.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SensorKind {
+    /// Degrees Celsius.
+    Temperature,
+    /// Revolutions per minute.
+    Fan,
+    /// Volts.
+    Voltage,
+    /// Watts.
+    Power,
+}
+
+impl SensorKind {
+    pub fn unit(self) -> &'static str {
+        match self {
+            SensorKind::Temperature => "°C",
+            SensorKind::Fan => "RPM",
+            SensorKind::Voltage => "V",
+            SensorKind::Power => "W",
+        }
+    }
+}
+
+impl std::fmt::Display for SensorKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            SensorKind::Temperature => "Temperature",
+            SensorKind::Fan => "Fan",
+            SensorKind::Voltage => "Voltage",
+            SensorKind::Power => "Power",
+        };
+        f.write_str(name)
+    }
+}
+
+/// A single physical graphics adapter, merged across the wgpu backends that expose it.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GpuSnapshot {
+    pub name: String,
+    pub device_type: GpuDeviceType,
+    /// wgpu backends (Vulkan, Gl, Dx12, ...) through which the adapter is reachable.
+    pub backends: Vec<String>,
+    /// PCI vendor ID, e.g. `0x10de` for NVIDIA.
+    pub vendor_id: u32,
+    /// PCI device ID.
+    pub device_id: u32,
+    pub driver: String,
+    pub driver_version: String,
+    /// Largest storage buffer a shader may bind. This is an API limit, not the VRAM size.
+    pub max_storage_buffer_binding_bytes: u64,
+    /// Dedicated video memory, when a driver interface reports it.
+    pub vram: Option<VramUsage>,
+    /// Live readings from a vendor API, when one is available for this adapter.
+    pub telemetry: Option<GpuTelemetry>,
+}
+
+/// How an adapter is attached to the system.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum GpuDeviceType {
+    Integrated,
+    Discrete,
+    Virtual,
+    Cpu,
+    Other,
+}
+
+impl std::fmt::Display for GpuDeviceType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            GpuDeviceType::Integrated => "Integrated",
+            GpuDeviceType::Discrete => "Discrete",
+            GpuDeviceType::Virtual => "Virtual",
+            GpuDeviceType::Cpu => "CPU",
+            GpuDeviceType::Other => "Other",
+        };
+        f.write_str(name)
+    }
+}
+
+/// Vendor-reported GPU readings. Each field is optional because drivers often
+/// leave individual queries unsupported.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct GpuTelemetry {
+    pub utilization_percent: Option<u32>,
+    pub memory_utilization_percent: Option<u32>,
+    pub graphics_clock_mhz: Option<u32>,
+    pub memory_clock_mhz: Option<u32>,
+    pub temperature_celsius: Option<u32>,
+    pub fan_speed_percent: Option<u32>,
+    pub power_draw_milliwatts: Option<u32>,
+}
+
+/// Dedicated video memory of one adapter, in bytes.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VramUsage {
+    pub total_bytes: u64,
+    pub used_bytes: Option<u64>,
+    pub source: VramSource,
+}
+
+/// Where a [`VramUsage`] reading came from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum VramSource {
+    /// NVIDIA Management Library.
+    Nvml,
+    /// amdgpu `mem_info_vram_*` files under `/sys/class/drm`.
+    AmdgpuSysfs,
+    /// i915 `i915_gem_objects` under debugfs.
+    I915Debugfs,
+    /// xe `vram0_mm` under debugfs.
+    XeDebugfs,
+}
+
+impl std::fmt::Display for VramSource {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            VramSource::Nvml => "NVML",
+            VramSource::AmdgpuSysfs => "amdgpu sysfs",
+            VramSource::I915Debugfs => "i915 debugfs",
+            VramSource::XeDebugfs => "xe debugfs",
+        };
+        f.write_str(name)
+    }
+}
+
+impl SystemSnapshot {
+    pub fn new() -> Self {
+        Self {
+            timestamp: SystemTime::now(),
+            cpu: CpuSnapshot::default(),
+            memory: MemorySnapshot::default(),
+            gpus: Vec::new(),
+            processes: Vec::new(),
+            storage: StorageSnapshot::default(),
+            networks: Vec::new(),
+            sensors: Vec::new(),
+        }
+    }
+
+    /// Folds a collector sample into the snapshot, replacing the matching section.
+    pub fn apply(&mut self, sample: Sample) {
+        match sample {
+            Sample::Cpu(cpu) => self.cpu = cpu,
+            Sample::Memory(memory) => self.memory = memory,
+            Sample::Gpu(gpus) => self.gpus = gpus,
+            Sample::Processes(processes) => self.processes = processes,
+            Sample::Storage(storage) => self.storage = storage,
+            Sample::Network(networks) => self.networks = networks,
+            Sample::Sensors(sensors) => self.sensors = sensors,
+        }
+    }
+}
+
+impl Default for SystemSnapshot {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn percent(part: u64, whole: u64) -> f32 {
+    if whole == 0 {
+        0.0
+    } else {
+        part as f32 / whole as f32 * 100.0
+    }
+}
+
+/// Received and transmitted bytes per second summed over every non-loopback interface.
+pub fn network_throughput(interfaces: &[InterfaceSnapshot]) -> (u64, u64) {
+    interfaces
+        .iter()
+        .filter(|interface| !interface.is_loopback())
+        .fold((0, 0), |(received, transmitted), interface| {
+            (
+                received + interface.received_bytes_per_sec,
+                transmitted + interface.transmitted_bytes_per_sec,
+            )
+        })
+}
+
+/// Converts a byte count to whole mebibytes for display.
+pub fn bytes_to_mb(bytes: u64) -> u64 {
+    bytes / 1024 / 1024
+}
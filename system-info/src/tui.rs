@@ -0,0 +1,49 @@
+use std::error::Error;
+use std::io;
+use std::time::{Duration, Instant};
+
+use ratatui::crossterm::event::{self, Event, KeyEventKind};
+use ratatui::DefaultTerminal;
+
+use crate::collector::CollectorRegistry;
+use crate::monitor::Monitor;
+
+mod app;
+mod view;
+
+pub use app::App;
+pub use view::draw;
+
+/// Longest wait between frames, so the clock-relative chart keeps scrolling when idle.
+const MAX_FRAME_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Runs the terminal frontend until the user quits. The terminal is restored even on error.
+pub fn run() -> Result<(), Box<dyn Error>> {
+    let mut monitor = Monitor::new(CollectorRegistry::with_defaults());
+    let mut terminal = ratatui::init();
+    let result = event_loop(&mut terminal, &mut monitor);
+    ratatui::restore();
+    Ok(result?)
+}
+
+fn event_loop(terminal: &mut DefaultTerminal, monitor: &mut Monitor) -> io::Result<()> {
+    let mut app = App::default();
+
+    while !app.should_quit {
+        let now = Instant::now();
+        monitor.poll(now);
+        terminal.draw(|frame| draw(frame, &mut app, monitor.snapshot(), monitor.history()))?;
+
+        // Sleep until the next collector is due, waking early for key presses
+        let wait = monitor.next_due(Instant::now()).min(MAX_FRAME_INTERVAL);
+        if event::poll(wait)? {
+            if let Event::Key(key) = event::read()? {
+                if key.kind == KeyEventKind::Press {
+                    app.handle_key(key);
+                }
+            }
+        }
+    }
+
+    Ok(())
+}
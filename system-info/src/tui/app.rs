@@ -0,0 +1,299 @@
+use std::cmp::Ordering;
+
+use ratatui::crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use ratatui::widgets::TableState;
+
+use crate::history::HistoryRange;
+use crate::snapshot::ProcessSnapshot;
+
+/// Rows skipped by Page Up and Page Down.
+const PAGE: usize = 10;
+
+/// Column the process table is ordered by.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SortKey {
+    Pid,
+    Name,
+    Cpu,
+    Memory,
+}
+
+impl SortKey {
+    fn compare(self, a: &ProcessSnapshot, b: &ProcessSnapshot) -> Ordering {
+        match self {
+            SortKey::Pid => a.pid.cmp(&b.pid),
+            SortKey::Name => a.name.to_lowercase().cmp(&b.name.to_lowercase()),
+            SortKey::Cpu => a.cpu_percent.total_cmp(&b.cpu_percent),
+            SortKey::Memory => a.memory_bytes.cmp(&b.memory_bytes),
+        }
+    }
+
+    /// Usage columns start with the busiest process, identifiers in natural order.
+    fn default_ascending(self) -> bool {
+        matches!(self, SortKey::Pid | SortKey::Name)
+    }
+}
+
+/// Everything the terminal UI remembers between frames, kept apart from the terminal
+/// so it can be driven from a test backend.
+#[derive(Debug)]
+pub struct App {
+    pub sort: SortKey,
+    pub ascending: bool,
+    pub filter: String,
+    /// Keys go to the filter instead of the shortcuts while this is set.
+    pub editing_filter: bool,
+    pub range: HistoryRange,
+    pub table: TableState,
+    pub should_quit: bool,
+}
+
+impl Default for App {
+    fn default() -> Self {
+        Self {
+            sort: SortKey::Cpu,
+            ascending: false,
+            filter: String::new(),
+            editing_filter: false,
+            range: HistoryRange::Recent,
+            table: TableState::default().with_selected(0),
+            should_quit: false,
+        }
+    }
+}
+
+impl App {
+    pub fn handle_key(&mut self, key: KeyEvent) {
+        if key.code == KeyCode::Char('c') && key.modifiers.contains(KeyModifiers::CONTROL) {
+            self.should_quit = true;
+            return;
+        }
+        if self.editing_filter {
+            self.edit_filter(key.code);
+            return;
+        }
+
+        match key.code {
+            KeyCode::Char('q') => self.should_quit = true,
+            KeyCode::Esc if !self.filter.is_empty() => self.filter.clear(),
+            KeyCode::Esc => self.should_quit = true,
+            KeyCode::Char('/') => self.editing_filter = true,
+            KeyCode::Down | KeyCode::Char('j') => self.move_selection(1),
+            KeyCode::Up | KeyCode::Char('k') => self.move_selection(-1),
+            KeyCode::PageDown => self.move_selection(PAGE as isize),
+            KeyCode::PageUp => self.move_selection(-(PAGE as isize)),
+            KeyCode::Home | KeyCode::Char('g') => self.table.select_first(),
+            KeyCode::End | KeyCode::Char('G') => self.table.select_last(),
+            KeyCode::Char('c') => self.sort_by(SortKey::Cpu),
+            KeyCode::Char('m') => self.sort_by(SortKey::Memory),
+            KeyCode::Char('p') => self.sort_by(SortKey::Pid),
+            KeyCode::Char('n') => self.sort_by(SortKey::Name),
+            KeyCode::Char('h') => {
+                self.range = match self.range {
+                    HistoryRange::Recent => HistoryRange::LongTerm,
+                    HistoryRange::LongTerm => HistoryRange::Recent,
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn edit_filter(&mut self, code: KeyCode) {
+        match code {
+            KeyCode::Enter => self.editing_filter = false,
+            KeyCode::Esc => {
+                self.filter.clear();
+                self.editing_filter = false;
+            }
+            KeyCode::Backspace => {
+                self.filter.pop();
+            }
+            KeyCode::Char(c) => self.filter.push(c),
+            _ => return,
+        }
+        // The old selection is meaningless once the list changes shape
+        self.table.select_first();
+    }
+
+    /// Picking the current column again flips the direction.
+    fn sort_by(&mut self, key: SortKey) {
+        if self.sort == key {
+            self.ascending = !self.ascending;
+        } else {
+            self.sort = key;
+            self.ascending = key.default_ascending();
+        }
+    }
+
+    fn move_selection(&mut self, delta: isize) {
+        let selected = self.table.selected().unwrap_or(0);
+        self.table
+            .select(Some(selected.saturating_add_signed(delta)));
+    }
+
+    /// Filters and sorts the process list the way the table shows it.
+    pub fn visible_processes<'a>(
+        &self,
+        processes: &'a [ProcessSnapshot],
+    ) -> Vec<&'a ProcessSnapshot> {
+        let filter = self.filter.to_lowercase();
+        let mut visible: Vec<&ProcessSnapshot> = processes
+            .iter()
+            .filter(|process| process.matches_filter(&filter))
+            .collect();
+
+        visible.sort_by(|a, b| self.sort.compare(a, b));
+        if !self.ascending {
+            visible.reverse();
+        }
+        visible
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ratatui::backend::TestBackend;
+    use ratatui::Terminal;
+
+    use super::*;
+    use crate::history::MetricsHistory;
+    use crate::snapshot::SystemSnapshot;
+    use crate::tui::draw;
+
+    fn snapshot() -> SystemSnapshot {
+        let process = |pid: u32, name: &str, cpu_percent: f32| ProcessSnapshot {
+            pid,
+            name: name.to_string(),
+            cpu_percent,
+            ..Default::default()
+        };
+        let mut snapshot = SystemSnapshot::new();
+        snapshot.cpu.brand = "Test CPU".to_string();
+        snapshot.cpu.usage_percent = 42.0;
+        snapshot.processes = vec![
+            process(300, "idle", 0.5),
+            process(100, "busy", 80.0),
+            process(200, "medium", 10.0),
+        ];
+        snapshot
+    }
+
+    /// Renders one frame and returns the screen as lines of text.
+    fn render(app: &mut App) -> Vec<String> {
+        let mut terminal = Terminal::new(TestBackend::new(120, 40)).unwrap();
+        let snapshot = snapshot();
+        let history = MetricsHistory::default();
+        terminal
+            .draw(|frame| draw(frame, app, &snapshot, &history))
+            .unwrap();
+
+        let buffer = terminal.backend().buffer();
+        (0..buffer.area.height)
+            .map(|y| {
+                (0..buffer.area.width)
+                    .map(|x| buffer[(x, y)].symbol())
+                    .collect()
+            })
+            .collect()
+    }
+
+    fn press(app: &mut App, code: KeyCode) {
+        app.handle_key(KeyEvent::new(code, KeyModifiers::NONE));
+    }
+
+    /// Names in the order the process table shows them.
+    fn process_order(screen: &[String]) -> Vec<&'static str> {
+        let mut rows: Vec<(usize, &'static str)> = ["idle", "busy", "medium"]
+            .into_iter()
+            .filter_map(|name| {
+                let row = screen
+                    .iter()
+                    .position(|line| line.contains(&format!(" {} ", name)))?;
+                Some((row, name))
+            })
+            .collect();
+        rows.sort();
+        rows.into_iter().map(|(_, name)| name).collect()
+    }
+
+    #[test]
+    fn draws_header_gauges_and_processes_from_the_snapshot() {
+        let screen = render(&mut App::default());
+        assert!(screen[0].contains("Test CPU"));
+        assert!(screen[0].contains("3 processes"));
+        assert!(screen.iter().any(|line| line.contains("42.0%")));
+        assert!(screen
+            .iter()
+            .any(|line| line.contains("Processes (3 shown)")));
+        assert!(screen.iter().any(|line| line.contains("CPU %▼")));
+        assert_eq!(process_order(&screen), ["busy", "medium", "idle"]);
+    }
+
+    #[test]
+    fn sort_keys_pick_a_column_and_flip_its_direction() {
+        let mut app = App::default();
+
+        press(&mut app, KeyCode::Char('p'));
+        let screen = render(&mut app);
+        assert!(screen.iter().any(|line| line.contains("PID▲")));
+        assert_eq!(process_order(&screen), ["busy", "medium", "idle"]);
+
+        press(&mut app, KeyCode::Char('p'));
+        let screen = render(&mut app);
+        assert!(screen.iter().any(|line| line.contains("PID▼")));
+        assert_eq!(process_order(&screen), ["idle", "medium", "busy"]);
+
+        press(&mut app, KeyCode::Char('n'));
+        assert_eq!(process_order(&render(&mut app)), ["busy", "idle", "medium"]);
+    }
+
+    #[test]
+    fn h_switches_the_chart_between_recent_and_long_term_history() {
+        let mut app = App::default();
+        let has_title = |screen: &[String], title: &str| screen.iter().any(|l| l.contains(title));
+
+        assert!(has_title(&render(&mut app), "History (last 10 minutes)"));
+        press(&mut app, KeyCode::Char('h'));
+        assert!(has_title(&render(&mut app), "History (last 24 hours)"));
+        press(&mut app, KeyCode::Char('h'));
+        assert!(has_title(&render(&mut app), "History (last 10 minutes)"));
+    }
+
+    #[test]
+    fn filter_captures_keys_until_enter() {
+        let mut app = App::default();
+        press(&mut app, KeyCode::Char('/'));
+        // 'q' is part of the filter text here, not a shortcut
+        for c in "medq".chars() {
+            press(&mut app, KeyCode::Char(c));
+        }
+        assert!(!app.should_quit);
+        press(&mut app, KeyCode::Backspace);
+        press(&mut app, KeyCode::Enter);
+        assert_eq!(app.filter, "med");
+
+        let screen = render(&mut app);
+        assert!(screen
+            .iter()
+            .any(|line| line.contains("Processes (1 shown)")));
+        assert_eq!(process_order(&screen), ["medium"]);
+
+        // The first Esc clears the filter, the second quits
+        press(&mut app, KeyCode::Esc);
+        assert!(app.filter.is_empty() && !app.should_quit);
+        press(&mut app, KeyCode::Esc);
+        assert!(app.should_quit);
+    }
+
+    #[test]
+    fn q_and_ctrl_c_quit() {
+        let mut app = App::default();
+        press(&mut app, KeyCode::Char('q'));
+        assert!(app.should_quit);
+
+        let mut app = App::default();
+        press(&mut app, KeyCode::Char('/'));
+        app.handle_key(KeyEvent::new(KeyCode::Char('c'), KeyModifiers::CONTROL));
+        assert!(app.should_quit);
+    }
+}
@@ -0,0 +1,255 @@
+use std::time::SystemTime;
+
+use ratatui::layout::{Constraint, Layout, Rect};
+use ratatui::style::{Color, Modifier, Style, Stylize};
+use ratatui::symbols::Marker;
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{
+    Axis, Block, Cell, Chart, Dataset, Gauge, GraphType, Paragraph, Row, Table,
+};
+use ratatui::Frame;
+
+use super::app::{App, SortKey};
+use crate::format::{format_rate, format_size};
+use crate::history::{HistoryRange, Metric, MetricsHistory};
+use crate::snapshot::SystemSnapshot;
+
+/// Seconds of history shown for each range, matching what `MetricsHistory` keeps.
+fn window_secs(range: HistoryRange) -> f64 {
+    match range {
+        HistoryRange::Recent => 10.0 * 60.0,
+        HistoryRange::LongTerm => 24.0 * 60.0 * 60.0,
+    }
+}
+
+fn metric_color(metric: Metric) -> Color {
+    match metric {
+        Metric::Cpu => Color::Cyan,
+        Metric::Ram => Color::Green,
+        Metric::Swap => Color::Yellow,
+        Metric::Gpu => Color::Magenta,
+        Metric::NetworkReceived | Metric::NetworkTransmitted => Color::Blue,
+    }
+}
+
+/// Draws one frame: header, gauges, history chart, process table and key help.
+pub fn draw(frame: &mut Frame, app: &mut App, snapshot: &SystemSnapshot, history: &MetricsHistory) {
+    let [header, gauges, chart, processes, footer] = Layout::vertical([
+        Constraint::Length(1),
+        Constraint::Length(3),
+        Constraint::Percentage(35),
+        Constraint::Min(5),
+        Constraint::Length(1),
+    ])
+    .areas(frame.area());
+
+    frame.render_widget(
+        Line::from(vec![
+            Span::from(snapshot.cpu.brand.as_str()).bold(),
+            Span::from(format!(
+                "  {} cores, {} processes",
+                snapshot.cpu.cores.len(),
+                snapshot.processes.len()
+            )),
+        ]),
+        header,
+    );
+    draw_gauges(frame, gauges, snapshot);
+    draw_chart(frame, chart, app.range, history);
+    draw_processes(frame, processes, app, snapshot);
+    draw_footer(frame, footer, app);
+}
+
+fn draw_gauges(frame: &mut Frame, area: Rect, snapshot: &SystemSnapshot) {
+    let memory = &snapshot.memory;
+    // Only vendor telemetry reports load; show the first GPU that has it
+    let gpu = snapshot
+        .gpus
+        .iter()
+        .find_map(|gpu| gpu.telemetry.as_ref()?.utilization_percent);
+
+    let gauges = [
+        (
+            Metric::Cpu,
+            Some(snapshot.cpu.usage_percent),
+            format!("{:.1}%", snapshot.cpu.usage_percent),
+        ),
+        (
+            Metric::Ram,
+            Some(memory.used_percent()),
+            format!(
+                "{} / {}",
+                format_size(memory.used_bytes),
+                format_size(memory.total_bytes)
+            ),
+        ),
+        (
+            Metric::Swap,
+            memory.swap_used_percent(),
+            match memory.swap_used_percent() {
+                Some(_) => format!(
+                    "{} / {}",
+                    format_size(memory.swap_used_bytes),
+                    format_size(memory.swap_total_bytes)
+                ),
+                None => "none".to_string(),
+            },
+        ),
+        (
+            Metric::Gpu,
+            gpu.map(|percent| percent as f32),
+            gpu.map_or("N/A".to_string(), |percent| format!("{}%", percent)),
+        ),
+    ];
+
+    let areas = Layout::horizontal([Constraint::Ratio(1, 4); 4]).split(area);
+    for ((metric, percent, label), area) in gauges.into_iter().zip(areas.iter()) {
+        let ratio = f64::from(percent.unwrap_or(0.0).clamp(0.0, 100.0)) / 100.0;
+        frame.render_widget(
+            Gauge::default()
+                .block(Block::bordered().title(metric.label()))
+                .gauge_style(metric_color(metric))
+                .ratio(ratio)
+                .label(label),
+            *area,
+        );
+    }
+}
+
+fn draw_chart(frame: &mut Frame, area: Rect, range: HistoryRange, history: &MetricsHistory) {
+    let now = SystemTime::now();
+    let window = window_secs(range);
+
+    // X is seconds relative to now, so the newest point sits at the right edge
+    let series: Vec<(Metric, Vec<(f64, f64)>)> = Metric::PERCENTAGES
+        .iter()
+        .map(|metric| {
+            let points = history
+                .series(*metric)
+                .points(range)
+                .iter()
+                .filter_map(|point| {
+                    let age = now.duration_since(point.timestamp).ok()?.as_secs_f64();
+                    (age <= window).then_some((-age, f64::from(point.value)))
+                })
+                .collect();
+            (*metric, points)
+        })
+        .collect();
+
+    let datasets = series
+        .iter()
+        .filter(|(_, points)| !points.is_empty())
+        .map(|(metric, points)| {
+            Dataset::default()
+                .name(metric.label())
+                .marker(Marker::Braille)
+                .graph_type(GraphType::Line)
+                .style(metric_color(*metric))
+                .data(points)
+        })
+        .collect();
+
+    let (title, oldest) = match range {
+        HistoryRange::Recent => ("History (last 10 minutes)", "-10m"),
+        HistoryRange::LongTerm => ("History (last 24 hours)", "-24h"),
+    };
+    let chart = Chart::new(datasets)
+        .block(Block::bordered().title(title))
+        .x_axis(
+            Axis::default()
+                .bounds([-window, 0.0])
+                .labels([oldest, "now"]),
+        )
+        .y_axis(
+            Axis::default()
+                .bounds([0.0, 100.0])
+                .labels(["0%", "50%", "100%"]),
+        );
+    frame.render_widget(chart, area);
+}
+
+fn draw_processes(frame: &mut Frame, area: Rect, app: &mut App, snapshot: &SystemSnapshot) {
+    let visible = app.visible_processes(&snapshot.processes);
+
+    // Keep the selection on a real row as the list grows and shrinks
+    match app.table.selected() {
+        _ if visible.is_empty() => app.table.select(None),
+        Some(selected) if selected >= visible.len() => app.table.select(Some(visible.len() - 1)),
+        None => app.table.select(Some(0)),
+        Some(_) => {}
+    }
+
+    let arrow = if app.ascending { "▲" } else { "▼" };
+    let title = |label: &'static str, key: SortKey| -> Cell<'static> {
+        if app.sort == key {
+            Cell::from(format!("{}{}", label, arrow))
+        } else {
+            Cell::from(label)
+        }
+    };
+    let header = Row::new([
+        title("PID", SortKey::Pid),
+        title("Name", SortKey::Name),
+        Cell::from("User"),
+        title("CPU %", SortKey::Cpu),
+        title("Memory", SortKey::Memory),
+        Cell::from("Disk Read"),
+        Cell::from("Disk Write"),
+        Cell::from("Command"),
+    ])
+    .style(Style::new().add_modifier(Modifier::BOLD));
+
+    let rows = visible.iter().map(|process| {
+        Row::new([
+            process.pid.to_string(),
+            process.name.clone(),
+            process.user.clone(),
+            format!("{:.1}", process.cpu_percent),
+            format_size(process.memory_bytes),
+            format_rate(process.disk_read_bytes_per_sec),
+            format_rate(process.disk_write_bytes_per_sec),
+            process.command.clone(),
+        ])
+    });
+
+    let table = Table::new(
+        rows,
+        [
+            Constraint::Length(8),
+            Constraint::Length(20),
+            Constraint::Length(10),
+            Constraint::Length(7),
+            Constraint::Length(10),
+            Constraint::Length(11),
+            Constraint::Length(11),
+            Constraint::Fill(1),
+        ],
+    )
+    .header(header)
+    .block(Block::bordered().title(format!("Processes ({} shown)", visible.len())))
+    .row_highlight_style(Style::new().reversed());
+
+    frame.render_stateful_widget(table, area, &mut app.table);
+}
+
+fn draw_footer(frame: &mut Frame, area: Rect, app: &App) {
+    let line = if app.editing_filter {
+        Line::from(vec![
+            Span::from("Filter: ").bold(),
+            Span::from(app.filter.as_str()),
+            Span::from("█"),
+            Span::from("  Enter keep, Esc clear").dark_gray(),
+        ])
+    } else {
+        let mut spans = vec![Span::from(
+            "q quit  ↑↓ select  c/m/p/n sort by CPU/memory/PID/name  / filter  h 24h history",
+        )
+        .dark_gray()];
+        if !app.filter.is_empty() {
+            spans.push(Span::from(format!("  filter: {}", app.filter)).bold());
+        }
+        Line::from(spans)
+    };
+    frame.render_widget(Paragraph::new(line), area);
+}
@@ -0,0 +1,236 @@
+use chrono::{DateTime, Local};
+use slint::{ModelRc, SharedString, VecModel};
+
+use crate::format::format_rate;
+use crate::history::{CoreHistory, HistoryRange, Metric, MetricsHistory, CORE_HISTORY_LEN};
+use crate::monitor::MonitorView;
+use crate::snapshot::{
+    bytes_to_mb, CpuSnapshot, GpuTelemetry, ProcessSnapshot, SystemSnapshot, VramUsage,
+};
+use crate::{AppWindow, ChartData, CoreRow};
+
+mod disks;
+mod memory;
+mod network;
+mod process_tree;
+mod processes;
+mod sensors;
+
+pub use process_tree::toggle as toggle_tree_node;
+
+/// Renders whichever process view is active.
+pub fn render_processes(ui: &AppWindow, processes: &[ProcessSnapshot]) {
+    if ui.get_process_tree_view() {
+        process_tree::render(ui, processes);
+    } else {
+        processes::render(ui, processes);
+    }
+}
+
+/// Formats the GPU section of a snapshot for display.
+fn format_gpu_info(snapshot: &SystemSnapshot) -> String {
+    if snapshot.gpus.is_empty() {
+        return "No GPU adapters found".to_string();
+    }
+
+    let mut gpu_info = String::new();
+    for gpu in &snapshot.gpus {
+        gpu_info.push_str(&format!("GPU: {} ({})\n", gpu.name, gpu.device_type));
+        gpu_info.push_str(&format!(
+            "PCI ID: {:04x}:{:04x}, Backends: {}\n",
+            gpu.vendor_id,
+            gpu.device_id,
+            gpu.backends.join(", ")
+        ));
+        if !gpu.driver.is_empty() {
+            gpu_info.push_str(&format!("Driver: {} {}\n", gpu.driver, gpu.driver_version));
+        }
+        gpu_info.push_str(&format_vram(gpu.vram.as_ref()));
+        gpu_info.push_str(&format!(
+            "Max storage buffer binding: {} MB\n",
+            bytes_to_mb(gpu.max_storage_buffer_binding_bytes)
+        ));
+
+        match &gpu.telemetry {
+            Some(telemetry) => gpu_info.push_str(&format_gpu_telemetry(telemetry)),
+            None => gpu_info.push_str("Clock Speed: N/A (requires vendor-specific APIs)\n"),
+        }
+        gpu_info.push('\n'); // Blank line between adapters
+    }
+
+    gpu_info.trim_end().to_string()
+}
+
+/// Formats an optional reading, falling back to "N/A" when the driver did not report it.
+fn format_reading<T: std::fmt::Display>(value: Option<T>, unit: &str) -> String {
+    match value {
+        Some(value) => format!("{}{}", value, unit),
+        None => "N/A".to_string(),
+    }
+}
+
+/// Formats the VRAM line, noting which driver interface reported it.
+fn format_vram(vram: Option<&VramUsage>) -> String {
+    match vram {
+        Some(VramUsage {
+            total_bytes,
+            used_bytes: Some(used_bytes),
+            source,
+        }) => format!(
+            "VRAM: {} / {} MB used ({})\n",
+            bytes_to_mb(*used_bytes),
+            bytes_to_mb(*total_bytes),
+            source
+        ),
+        Some(VramUsage {
+            total_bytes,
+            used_bytes: None,
+            source,
+        }) => format!("VRAM: {} MB ({})\n", bytes_to_mb(*total_bytes), source),
+        None => "VRAM: N/A\n".to_string(),
+    }
+}
+
+/// Formats vendor telemetry for a single GPU, one reading group per line.
+fn format_gpu_telemetry(telemetry: &GpuTelemetry) -> String {
+    let power_watts = telemetry
+        .power_draw_milliwatts
+        .map(|milliwatts| format!("{:.1}", milliwatts as f32 / 1000.0));
+
+    format!(
+        "Utilization: {} (memory {})\n\
+         Clock Speed: {} graphics, {} memory\n\
+         Temperature: {}, Fan: {}, Power: {}\n",
+        format_reading(telemetry.utilization_percent, "%"),
+        format_reading(telemetry.memory_utilization_percent, "%"),
+        format_reading(telemetry.graphics_clock_mhz, " MHz"),
+        format_reading(telemetry.memory_clock_mhz, " MHz"),
+        format_reading(telemetry.temperature_celsius, " °C"),
+        format_reading(telemetry.fan_speed_percent, "%"),
+        format_reading(power_watts, " W"),
+    )
+}
+
+/// Builds Slint path commands for a line of percentages in a 100x100 viewbox,
+/// spacing the points as if there were `slots` of them.
+fn path_commands(values: impl Iterator<Item = f32>, slots: usize) -> String {
+    let step = 100.0 / slots.saturating_sub(1).max(1) as f32;
+    let mut commands = String::new();
+
+    for (index, value) in values.enumerate() {
+        let command = if index == 0 { 'M' } else { 'L' };
+        let y = 100.0 - value.clamp(0.0, 100.0);
+        commands.push_str(&format!("{} {:.1} {:.1} ", command, index as f32 * step, y));
+    }
+
+    commands.trim_end().to_string()
+}
+
+/// Builds one row per logical core for the per-core list.
+fn core_rows(cpu: &CpuSnapshot, core_history: &CoreHistory) -> Vec<CoreRow> {
+    cpu.cores
+        .iter()
+        .enumerate()
+        .map(|(index, core)| CoreRow {
+            name: core.name.clone().into(),
+            usage: core.usage_percent,
+            frequency: format!("{} MHz", core.frequency_mhz).into(),
+            sparkline: path_commands(core_history.core(index), CORE_HISTORY_LEN).into(),
+        })
+        .collect()
+}
+
+/// Formats a metric value in its own unit.
+fn format_metric(metric: Metric, value: f32) -> String {
+    if metric.is_percentage() {
+        format!("{:.1}%", value)
+    } else {
+        format_rate(value as u64)
+    }
+}
+
+/// Prepares the chart of one metric over the selected range.
+fn chart_data(history: &MetricsHistory, metric: Metric, range: HistoryRange) -> ChartData {
+    let points = history.series(metric).points(range);
+    let time_format = match range {
+        HistoryRange::Recent => "%H:%M:%S",
+        HistoryRange::LongTerm => "%a %H:%M",
+    };
+
+    // Charts plot 0 to 100; rates are scaled so the busiest point in view fills the height
+    let scale = if metric.is_percentage() {
+        1.0
+    } else {
+        let peak = points.iter().map(|point| point.value).fold(0.0, f32::max);
+        100.0 / peak.max(1.0)
+    };
+
+    let values: Vec<f32> = points.iter().map(|point| point.value * scale).collect();
+    let labels: Vec<SharedString> = points
+        .iter()
+        .map(|point| {
+            let time = DateTime::<Local>::from(point.timestamp).format(time_format);
+            format!("{} at {}", format_metric(metric, point.value), time).into()
+        })
+        .collect();
+    let latest = match points.iter().last() {
+        Some(point) => format_metric(metric, point.value),
+        None => "No data".to_string(),
+    };
+
+    ChartData {
+        title: metric.label().into(),
+        latest: latest.into(),
+        commands: path_commands(values.iter().copied(), values.len()).into(),
+        values: ModelRc::new(VecModel::from(values)),
+        labels: ModelRc::new(VecModel::from(labels)),
+    }
+}
+
+/// Renders the monitor state into the UI.
+pub fn render(ui: &AppWindow, view: &MonitorView) {
+    let snapshot = &view.snapshot;
+
+    // Update CPU usage
+    ui.set_cpu_brand(format!("{} ({})", snapshot.cpu.brand, snapshot.cpu.vendor_id).into());
+    ui.set_cpu_usage(format!("CPU Usage: {:.2}%", snapshot.cpu.usage_percent).into());
+    ui.set_cores(ModelRc::new(VecModel::from(core_rows(
+        &snapshot.cpu,
+        &view.core_history,
+    ))));
+
+    // Update RAM and swap usage
+    memory::render(ui, &snapshot.memory);
+
+    // Update GPU information
+    ui.set_gpu_info(format_gpu_info(snapshot).into());
+
+    // Update history charts
+    let range = if ui.get_show_long_term() {
+        HistoryRange::LongTerm
+    } else {
+        HistoryRange::Recent
+    };
+    let charts: Vec<ChartData> = Metric::PERCENTAGES
+        .iter()
+        .map(|metric| chart_data(&view.history, *metric, range))
+        .collect();
+    ui.set_charts(ModelRc::new(VecModel::from(charts)));
+
+    // Update network interfaces and their throughput charts
+    let network_charts: Vec<ChartData> = Metric::NETWORK
+        .iter()
+        .map(|metric| chart_data(&view.history, *metric, range))
+        .collect();
+    ui.set_network_charts(ModelRc::new(VecModel::from(network_charts)));
+    network::render(ui, &snapshot.networks);
+
+    // Update mounts and block device throughput
+    disks::render(ui, &snapshot.storage);
+
+    // Update hardware sensors
+    sensors::render(ui, &snapshot.sensors);
+
+    // Update the process table or tree
+    render_processes(ui, &snapshot.processes);
+}
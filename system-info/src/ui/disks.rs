@@ -0,0 +1,60 @@
+use slint::{ModelRc, VecModel};
+
+use crate::format::{format_rate, format_size};
+use crate::snapshot::{BlockDeviceSnapshot, MountSnapshot, StorageSnapshot};
+use crate::{AlertLevel, AppWindow, BlockDeviceRow, MountRow};
+
+/// Usage at which a mount is flagged as filling up.
+const WARNING_PERCENT: f32 = 85.0;
+
+/// Usage at which a mount is flagged as almost full.
+const CRITICAL_PERCENT: f32 = 95.0;
+
+fn usage_level(used_percent: f32) -> AlertLevel {
+    if used_percent >= CRITICAL_PERCENT {
+        AlertLevel::Critical
+    } else if used_percent >= WARNING_PERCENT {
+        AlertLevel::Warning
+    } else {
+        AlertLevel::Normal
+    }
+}
+
+fn mount_row(mount: &MountSnapshot) -> MountRow {
+    let used_percent = mount.used_percent();
+
+    MountRow {
+        mount_point: mount.mount_point.as_str().into(),
+        device: mount.device.as_str().into(),
+        file_system: mount.file_system.as_str().into(),
+        removable: mount.is_removable,
+        capacity: format!(
+            "{} free of {}",
+            format_size(mount.available_bytes),
+            format_size(mount.total_bytes)
+        )
+        .into(),
+        usage: used_percent / 100.0,
+        usage_text: format!("{:.0}%", used_percent).into(),
+        level: usage_level(used_percent),
+    }
+}
+
+fn device_row(device: &BlockDeviceSnapshot) -> BlockDeviceRow {
+    BlockDeviceRow {
+        name: device.name.as_str().into(),
+        read: format_rate(device.read_bytes_per_sec).into(),
+        write: format_rate(device.write_bytes_per_sec).into(),
+        read_iops: format!("{:.0}", device.read_iops).into(),
+        write_iops: format!("{:.0}", device.write_iops).into(),
+    }
+}
+
+/// Renders the mount list and block device throughput.
+pub fn render(ui: &AppWindow, storage: &StorageSnapshot) {
+    let mounts: Vec<MountRow> = storage.mounts.iter().map(mount_row).collect();
+    ui.set_mounts(ModelRc::new(VecModel::from(mounts)));
+
+    let devices: Vec<BlockDeviceRow> = storage.devices.iter().map(device_row).collect();
+    ui.set_block_devices(ModelRc::new(VecModel::from(devices)));
+}
@@ -0,0 +1,111 @@
+use slint::{ModelRc, VecModel};
+
+use crate::format::format_size;
+use crate::snapshot::{MemoryBreakdown, MemorySnapshot};
+use crate::{AppWindow, MemorySegment};
+
+/// Splits physical memory into non-overlapping parts for the stacked bar.
+///
+/// Shared memory lives in the page cache but cannot be dropped, so it gets its
+/// own segment and is taken out of the cache figure.
+fn segments(
+    total_bytes: u64,
+    free_bytes: u64,
+    breakdown: &MemoryBreakdown,
+) -> Vec<(&'static str, u64)> {
+    let cache = breakdown
+        .cached_bytes
+        .saturating_sub(breakdown.shared_bytes);
+    let huge_pages = breakdown.huge_pages_bytes();
+    let applications = total_bytes
+        .saturating_sub(free_bytes)
+        .saturating_sub(breakdown.buffers_bytes)
+        .saturating_sub(breakdown.cached_bytes)
+        .saturating_sub(breakdown.slab_bytes)
+        .saturating_sub(huge_pages);
+
+    vec![
+        ("Applications", applications),
+        ("Shared", breakdown.shared_bytes),
+        ("Slab", breakdown.slab_bytes),
+        ("Huge pages", huge_pages),
+        ("Buffers", breakdown.buffers_bytes),
+        ("Page cache", cache),
+        ("Free", free_bytes),
+    ]
+}
+
+fn memory_segments(memory: &MemorySnapshot) -> Vec<MemorySegment> {
+    let parts = match &memory.breakdown {
+        Some(breakdown) => segments(memory.total_bytes, memory.free_bytes, breakdown),
+        // Without /proc/meminfo only the used/free split is known
+        None => vec![("Used", memory.used_bytes), ("Free", memory.free_bytes)],
+    };
+    let total = memory.total_bytes.max(1) as f32;
+
+    parts
+        .into_iter()
+        .map(|(label, bytes)| MemorySegment {
+            label: label.into(),
+            fraction: bytes as f32 / total,
+            size: format_size(bytes).into(),
+        })
+        .collect()
+}
+
+fn format_swap(memory: &MemorySnapshot) -> String {
+    match memory.swap_used_percent() {
+        Some(percent) => format!(
+            "Swap: {} used of {} ({:.1}%)",
+            format_size(memory.swap_used_bytes),
+            format_size(memory.swap_total_bytes),
+            percent
+        ),
+        None => "Swap: none".to_string(),
+    }
+}
+
+fn format_details(breakdown: &MemoryBreakdown) -> String {
+    let mut details = format!(
+        "Dirty: {}, Writeback: {}",
+        format_size(breakdown.dirty_bytes),
+        format_size(breakdown.writeback_bytes)
+    );
+    if breakdown.huge_pages_total > 0 {
+        details.push_str(&format!(
+            ", Huge pages: {} of {} free ({} each)",
+            breakdown.huge_pages_free,
+            breakdown.huge_pages_total,
+            format_size(breakdown.huge_page_size_bytes)
+        ));
+    }
+    details
+}
+
+/// Renders the RAM summary, the stacked breakdown bar and swap usage.
+pub fn render(ui: &AppWindow, memory: &MemorySnapshot) {
+    let available = match &memory.breakdown {
+        Some(breakdown) => format!(", Available: {}", format_size(breakdown.available_bytes)),
+        None => String::new(),
+    };
+    ui.set_ram_info(
+        format!(
+            "Total RAM: {}, Used: {}, Free: {}{}",
+            format_size(memory.total_bytes),
+            format_size(memory.used_bytes),
+            format_size(memory.free_bytes),
+            available
+        )
+        .into(),
+    );
+    ui.set_swap_info(format_swap(memory).into());
+    ui.set_memory_details(
+        memory
+            .breakdown
+            .as_ref()
+            .map(format_details)
+            .unwrap_or_default()
+            .into(),
+    );
+    ui.set_memory_segments(ModelRc::new(VecModel::from(memory_segments(memory))));
+}
@@ -0,0 +1,59 @@
+use slint::{ModelRc, VecModel};
+
+use crate::format::{format_rate, format_size};
+use crate::snapshot::{network_throughput, InterfaceSnapshot};
+use crate::{AppWindow, InterfaceRow};
+
+fn format_drops(drops: Option<u64>) -> String {
+    drops.map_or_else(|| "N/A".to_string(), |drops| drops.to_string())
+}
+
+fn interface_row(interface: &InterfaceSnapshot) -> InterfaceRow {
+    InterfaceRow {
+        name: interface.name.as_str().into(),
+        mac_address: interface.mac_address.as_str().into(),
+        addresses: interface.addresses.join(", ").into(),
+        received: format!(
+            "{} ({:.0} pkt/s)",
+            format_rate(interface.received_bytes_per_sec),
+            interface.received_packets_per_sec
+        )
+        .into(),
+        transmitted: format!(
+            "{} ({:.0} pkt/s)",
+            format_rate(interface.transmitted_bytes_per_sec),
+            interface.transmitted_packets_per_sec
+        )
+        .into(),
+        totals: format!(
+            "{} in, {} out",
+            format_size(interface.total_received_bytes),
+            format_size(interface.total_transmitted_bytes)
+        )
+        .into(),
+        errors: format!(
+            "{} rx / {} tx errors, {} rx / {} tx dropped",
+            interface.receive_errors,
+            interface.transmit_errors,
+            format_drops(interface.receive_drops),
+            format_drops(interface.transmit_drops)
+        )
+        .into(),
+    }
+}
+
+/// Renders the interface list and the throughput summed across interfaces.
+pub fn render(ui: &AppWindow, interfaces: &[InterfaceSnapshot]) {
+    let rows: Vec<InterfaceRow> = interfaces.iter().map(interface_row).collect();
+    ui.set_interfaces(ModelRc::new(VecModel::from(rows)));
+
+    let (received, transmitted) = network_throughput(interfaces);
+    ui.set_network_total(
+        format!(
+            "Total (excluding loopback): {} received, {} transmitted",
+            format_rate(received),
+            format_rate(transmitted)
+        )
+        .into(),
+    );
+}
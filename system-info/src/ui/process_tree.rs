@@ -0,0 +1,64 @@
+use std::cell::RefCell;
+use std::collections::HashSet;
+
+use slint::{Model, ModelRc, VecModel};
+
+use crate::process_tree::{ProcessTree, TreeLine};
+use crate::snapshot::{bytes_to_mb, ProcessSnapshot};
+use crate::{AppWindow, TreeRow};
+
+thread_local! {
+    /// PIDs the user has collapsed. Owned by the UI thread, which is the only one rendering.
+    static COLLAPSED: RefCell<HashSet<u32>> = RefCell::new(HashSet::new());
+}
+
+/// Expands a collapsed process or collapses an expanded one.
+pub fn toggle(pid: u32) {
+    COLLAPSED.with(|collapsed| {
+        let mut collapsed = collapsed.borrow_mut();
+        if !collapsed.remove(&pid) {
+            collapsed.insert(pid);
+        }
+    });
+}
+
+fn tree_row(line: &TreeLine) -> TreeRow {
+    TreeRow {
+        pid: line.process.pid as i32,
+        name: line.process.name.as_str().into(),
+        depth: line.depth as i32,
+        has_children: line.has_children,
+        expanded: line.expanded,
+        cpu: format!("{:.1}", line.process.cpu_percent).into(),
+        memory: format!("{} MB", bytes_to_mb(line.process.memory_bytes)).into(),
+        subtree_cpu: format!("{:.1}", line.subtree_cpu_percent).into(),
+        subtree_memory: format!("{} MB", bytes_to_mb(line.subtree_memory_bytes)).into(),
+    }
+}
+
+/// Updates the process tree in place, replacing only rows that changed.
+pub fn render(ui: &AppWindow, processes: &[ProcessSnapshot]) {
+    let rows = ui.get_process_tree_rows();
+    let Some(model) = rows.as_any().downcast_ref::<VecModel<TreeRow>>() else {
+        // Before the first render the property holds no VecModel we can edit in place
+        ui.set_process_tree_rows(ModelRc::new(VecModel::<TreeRow>::default()));
+        return render(ui, processes);
+    };
+
+    let tree = ProcessTree::build(processes);
+    let lines = COLLAPSED.with(|collapsed| tree.lines(&collapsed.borrow()));
+
+    for (index, line) in lines.iter().enumerate() {
+        let row = tree_row(line);
+        match model.row_data(index) {
+            Some(existing) if existing == row => {}
+            Some(_) => model.set_row_data(index, row),
+            None => model.push(row),
+        }
+    }
+
+    // Collapsed branches and exited processes leave fewer lines than last time
+    while model.row_count() > lines.len() {
+        model.remove(model.row_count() - 1);
+    }
+}
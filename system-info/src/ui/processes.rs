@@ -0,0 +1,309 @@
+use std::cmp::Ordering;
+use std::collections::HashMap;
+
+use chrono::{DateTime, Local};
+use slint::{Model, ModelRc, SharedString, StandardListViewItem, VecModel};
+
+use crate::format::format_rate;
+use crate::snapshot::{bytes_to_mb, ProcessSnapshot};
+use crate::AppWindow;
+
+type RowModel = VecModel<StandardListViewItem>;
+type TableModel = VecModel<ModelRc<StandardListViewItem>>;
+
+/// Columns of the process table, in the order `AppWindow` declares them.
+#[derive(Debug, Clone, Copy)]
+enum Column {
+    Pid,
+    Name,
+    User,
+    Cpu,
+    Memory,
+    DiskRead,
+    DiskWrite,
+    Status,
+    Started,
+    Command,
+}
+
+const COLUMNS: [Column; 10] = [
+    Column::Pid,
+    Column::Name,
+    Column::User,
+    Column::Cpu,
+    Column::Memory,
+    Column::DiskRead,
+    Column::DiskWrite,
+    Column::Status,
+    Column::Started,
+    Column::Command,
+];
+
+impl Column {
+    fn from_index(index: i32) -> Option<Self> {
+        COLUMNS.get(usize::try_from(index).ok()?).copied()
+    }
+
+    fn cell(self, process: &ProcessSnapshot) -> SharedString {
+        match self {
+            Column::Pid => process.pid.to_string().into(),
+            Column::Name => process.name.as_str().into(),
+            Column::User => process.user.as_str().into(),
+            Column::Cpu => format!("{:.1}", process.cpu_percent).into(),
+            Column::Memory => format!("{} MB", bytes_to_mb(process.memory_bytes)).into(),
+            Column::DiskRead => format_rate(process.disk_read_bytes_per_sec).into(),
+            Column::DiskWrite => format_rate(process.disk_write_bytes_per_sec).into(),
+            Column::Status => process.status.as_str().into(),
+            Column::Started => format_start_time(process.start_time).into(),
+            Column::Command => process.command.as_str().into(),
+        }
+    }
+
+    /// Orders by the underlying value rather than the formatted text, so 10 MB sorts after 9 MB.
+    fn compare(self, a: &ProcessSnapshot, b: &ProcessSnapshot) -> Ordering {
+        match self {
+            Column::Pid => a.pid.cmp(&b.pid),
+            Column::Name => a.name.to_lowercase().cmp(&b.name.to_lowercase()),
+            Column::User => a.user.cmp(&b.user),
+            Column::Cpu => a.cpu_percent.total_cmp(&b.cpu_percent),
+            Column::Memory => a.memory_bytes.cmp(&b.memory_bytes),
+            Column::DiskRead => a.disk_read_bytes_per_sec.cmp(&b.disk_read_bytes_per_sec),
+            Column::DiskWrite => a.disk_write_bytes_per_sec.cmp(&b.disk_write_bytes_per_sec),
+            Column::Status => a.status.cmp(&b.status),
+            Column::Started => a.start_time.cmp(&b.start_time),
+            Column::Command => a.command.cmp(&b.command),
+        }
+    }
+}
+
+fn format_start_time(start_time: u64) -> String {
+    match DateTime::from_timestamp(start_time as i64, 0) {
+        Some(time) => time
+            .with_timezone(&Local)
+            .format("%Y-%m-%d %H:%M:%S")
+            .to_string(),
+        None => String::new(),
+    }
+}
+
+/// Filters and sorts the process list according to the table controls.
+fn visible_processes<'a>(
+    ui: &AppWindow,
+    processes: &'a [ProcessSnapshot],
+) -> Vec<&'a ProcessSnapshot> {
+    select_processes(
+        processes,
+        &ui.get_process_filter().to_lowercase(),
+        Column::from_index(ui.get_process_sort_column()),
+        ui.get_process_sort_ascending(),
+    )
+}
+
+/// Keeps the processes matching the lowercase `filter`, sorted by `sort` if given.
+fn select_processes<'a>(
+    processes: &'a [ProcessSnapshot],
+    filter: &str,
+    sort: Option<Column>,
+    ascending: bool,
+) -> Vec<&'a ProcessSnapshot> {
+    let mut visible: Vec<&ProcessSnapshot> = processes
+        .iter()
+        .filter(|process| process.matches_filter(filter))
+        .collect();
+
+    if let Some(column) = sort {
+        visible.sort_by(|a, b| column.compare(a, b));
+        if !ascending {
+            visible.reverse();
+        }
+    }
+
+    visible
+}
+
+fn new_row(cells: [SharedString; COLUMNS.len()]) -> ModelRc<StandardListViewItem> {
+    let items: Vec<StandardListViewItem> =
+        cells.into_iter().map(StandardListViewItem::from).collect();
+    ModelRc::new(RowModel::from(items))
+}
+
+fn update_cells(row: &RowModel, cells: [SharedString; COLUMNS.len()]) {
+    for (column, text) in cells.into_iter().enumerate() {
+        if row.row_data(column).map(|item| item.text) != Some(text.clone()) {
+            row.set_row_data(column, StandardListViewItem::from(text));
+        }
+    }
+}
+
+/// PID of a table row, read back from its first cell.
+fn row_pid(row: &ModelRc<StandardListViewItem>) -> Option<u32> {
+    row.row_data(0)?.text.parse().ok()
+}
+
+/// Brings the table in line with `visible`, touching only the cells whose text changed.
+///
+/// Each process keeps its row model across renders, so a row that moves after a re-sort
+/// is the same row. Returns where the process in row `selected` ended up, if it is still
+/// listed, so the selection can follow it.
+fn sync_rows(
+    table: &TableModel,
+    visible: &[&ProcessSnapshot],
+    selected: Option<usize>,
+) -> Option<usize> {
+    let selected_pid = selected
+        .and_then(|index| table.row_data(index))
+        .and_then(|row| row_pid(&row));
+    let mut rows: HashMap<u32, ModelRc<StandardListViewItem>> = table
+        .iter()
+        .filter_map(|row| Some((row_pid(&row)?, row)))
+        .collect();
+
+    for (index, process) in visible.iter().enumerate() {
+        let cells = COLUMNS.map(|column| column.cell(process));
+        let row = match rows.remove(&process.pid) {
+            Some(row) => match row.as_any().downcast_ref::<RowModel>() {
+                Some(cells_model) => {
+                    update_cells(cells_model, cells);
+                    row
+                }
+                None => new_row(cells),
+            },
+            None => new_row(cells),
+        };
+
+        match table.row_data(index) {
+            Some(current) if current == row => {}
+            Some(_) => table.set_row_data(index, row),
+            None => table.push(row),
+        }
+    }
+
+    // The filter or exited processes shortened the table; trim the surplus rows
+    while table.row_count() > visible.len() {
+        table.remove(table.row_count() - 1);
+    }
+
+    let selected_pid = selected_pid?;
+    visible
+        .iter()
+        .position(|process| process.pid == selected_pid)
+}
+
+/// Updates the process table in place and keeps the selection on the same process.
+pub fn render(ui: &AppWindow, processes: &[ProcessSnapshot]) {
+    let rows = ui.get_process_rows();
+    let Some(table) = rows.as_any().downcast_ref::<TableModel>() else {
+        // The window starts with an empty default model; swap in nested row models so
+        // later renders can rewrite single cells
+        ui.set_process_rows(ModelRc::new(TableModel::default()));
+        return render(ui, processes);
+    };
+
+    let visible = visible_processes(ui, processes);
+    let selected = usize::try_from(ui.get_process_selected_row()).ok();
+    let selected = sync_rows(table, &visible, selected);
+    ui.set_process_selected_row(selected.map_or(-1, |index| index as i32));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn process(pid: u32, name: &str, memory_bytes: u64) -> ProcessSnapshot {
+        ProcessSnapshot {
+            pid,
+            name: name.to_string(),
+            user: "alice".to_string(),
+            memory_bytes,
+            command: format!("/usr/bin/{}", name.to_lowercase()),
+            ..Default::default()
+        }
+    }
+
+    fn pids(processes: &[&ProcessSnapshot]) -> Vec<u32> {
+        processes.iter().map(|process| process.pid).collect()
+    }
+
+    fn table_pids(table: &TableModel) -> Vec<u32> {
+        table.iter().filter_map(|row| row_pid(&row)).collect()
+    }
+
+    #[test]
+    fn columns_sort_by_value_rather_than_text() {
+        let processes = [
+            process(300, "beta", 10 * 1024 * 1024),
+            process(20, "Alpha", 9 * 1024 * 1024),
+            process(1, "Gamma", 100 * 1024 * 1024),
+        ];
+
+        // As text, "10 MB" would sort before "9 MB" and "Gamma" before "beta"
+        let by_memory = select_processes(&processes, "", Some(Column::Memory), true);
+        assert_eq!(pids(&by_memory), [20, 300, 1]);
+        let by_pid = select_processes(&processes, "", Some(Column::Pid), false);
+        assert_eq!(pids(&by_pid), [300, 20, 1]);
+        let by_name = select_processes(&processes, "", Some(Column::Name), true);
+        assert_eq!(pids(&by_name), [20, 300, 1]);
+        // Without a sort column the collector's order is kept
+        assert_eq!(
+            pids(&select_processes(&processes, "", None, true)),
+            [300, 20, 1]
+        );
+    }
+
+    #[test]
+    fn the_filter_matches_name_user_command_and_pid() {
+        let mut processes = vec![
+            process(300, "Firefox", 0),
+            process(4242, "bash", 0),
+            process(7, "sshd", 0),
+        ];
+        processes[2].user = "root".to_string();
+
+        let matching = |filter: &str| pids(&select_processes(&processes, filter, None, true));
+        assert_eq!(matching("fire"), [300]);
+        assert_eq!(matching("root"), [7]);
+        assert_eq!(matching("/usr/bin/bash"), [4242]);
+        assert_eq!(matching("42"), [4242]);
+        assert_eq!(matching(""), [300, 4242, 7]);
+        assert!(matching("nothing").is_empty());
+    }
+
+    #[test]
+    fn rows_and_the_selection_follow_their_process_when_the_order_changes() {
+        let table = TableModel::default();
+        let mut processes = vec![
+            process(1, "init", 30),
+            process(2, "kthreadd", 20),
+            process(3, "bash", 10),
+        ];
+        let by_memory = |processes: &[ProcessSnapshot]| {
+            select_processes(processes, "", Some(Column::Memory), false)
+                .into_iter()
+                .cloned()
+                .collect::<Vec<_>>()
+        };
+
+        let first = by_memory(&processes);
+        assert_eq!(
+            sync_rows(&table, &first.iter().collect::<Vec<_>>(), None),
+            None
+        );
+        assert_eq!(table_pids(&table), [1, 2, 3]);
+        let bash_row = table.row_data(2).unwrap();
+
+        // bash grows to the top of the list while it is selected
+        processes[2].memory_bytes = 50;
+        let second = by_memory(&processes);
+        let selected = sync_rows(&table, &second.iter().collect::<Vec<_>>(), Some(2));
+        assert_eq!(table_pids(&table), [3, 1, 2]);
+        assert_eq!(selected, Some(0));
+        assert!(table.row_data(0).unwrap() == bash_row);
+
+        // Once the selected process is gone, nothing is selected
+        processes.remove(2);
+        let third = by_memory(&processes);
+        let selected = sync_rows(&table, &third.iter().collect::<Vec<_>>(), Some(0));
+        assert_eq!(table_pids(&table), [1, 2]);
+        assert_eq!(selected, None);
+    }
+}
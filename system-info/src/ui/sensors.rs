@@ -0,0 +1,53 @@
+use slint::{ModelRc, VecModel};
+
+use crate::snapshot::{SensorKind, SensorReading};
+use crate::{AlertLevel, AppWindow, SensorRow};
+
+fn format_value(kind: SensorKind, value: f64) -> String {
+    match kind {
+        SensorKind::Fan => format!("{:.0} {}", value, kind.unit()),
+        SensorKind::Voltage => format!("{:.3} {}", value, kind.unit()),
+        SensorKind::Temperature | SensorKind::Power => format!("{:.1} {}", value, kind.unit()),
+    }
+}
+
+/// Lists whichever limits the hardware reported, e.g. "min 300 RPM, crit 100.0 °C".
+fn format_limits(sensor: &SensorReading) -> String {
+    [
+        ("min", sensor.min),
+        ("max", sensor.max),
+        ("crit", sensor.critical),
+    ]
+    .iter()
+    .filter_map(|(name, limit)| Some(format!("{} {}", name, format_value(sensor.kind, (*limit)?))))
+    .collect::<Vec<_>>()
+    .join(", ")
+}
+
+fn sensor_row(sensor: &SensorReading) -> SensorRow {
+    let level = if sensor.is_critical() {
+        AlertLevel::Critical
+    } else if sensor.is_above_max() {
+        AlertLevel::Warning
+    } else {
+        AlertLevel::Normal
+    };
+
+    SensorRow {
+        kind: sensor.kind.to_string().into(),
+        label: sensor.label.as_str().into(),
+        value: format_value(sensor.kind, sensor.value).into(),
+        limits: format_limits(sensor).into(),
+        level,
+    }
+}
+
+/// Renders every sensor, grouped by kind.
+pub fn render(ui: &AppWindow, sensors: &[SensorReading]) {
+    let mut sensors: Vec<&SensorReading> = sensors.iter().collect();
+    // Stable sort keeps the collector's per-chip order within each kind
+    sensors.sort_by_key(|sensor| sensor.kind);
+
+    let rows: Vec<SensorRow> = sensors.into_iter().map(sensor_row).collect();
+    ui.set_sensors(ModelRc::new(VecModel::from(rows)));
+}
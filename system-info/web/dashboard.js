@@ -0,0 +1,320 @@
+// Live dashboard fed by the /api/stream WebSocket. Mirrors the Overview and
+// Processes tabs of the desktop window.
+"use strict";
+
+const HISTORY_LEN = 600; // Matches the ten minutes of raw history kept by the server
+const PROCESS_ROWS = 200;
+const CHART_METRICS = [
+    { key: "cpu", title: "CPU Usage" },
+    { key: "ram", title: "RAM Usage" },
+    { key: "swap", title: "Swap Usage" },
+    { key: "gpu", title: "GPU Usage" },
+];
+const MEMORY_COLORS = {
+    Applications: "#3a86ff",
+    Shared: "#8338ec",
+    Slab: "#ff006e",
+    "Huge pages": "#fb5607",
+    Buffers: "#ffbe0b",
+    "Page cache": "#06d6a0",
+    Used: "#3a86ff",
+};
+const PROCESS_COLUMNS = [
+    { title: "PID", value: (p) => p.pid },
+    { title: "Name", value: (p) => p.name },
+    { title: "User", value: (p) => p.user ?? "" },
+    { title: "CPU %", value: (p) => p.cpu_percent, text: (p) => p.cpu_percent.toFixed(1) },
+    { title: "Memory", value: (p) => p.memory_bytes, text: (p) => formatSize(p.memory_bytes) },
+    { title: "Disk Read", value: (p) => p.disk_read_bytes_per_sec, text: (p) => formatRate(p.disk_read_bytes_per_sec) },
+    { title: "Disk Write", value: (p) => p.disk_write_bytes_per_sec, text: (p) => formatRate(p.disk_write_bytes_per_sec) },
+    { title: "Status", value: (p) => p.status },
+    { title: "Started", value: (p) => p.start_time, text: (p) => new Date(p.start_time * 1000).toLocaleString() },
+    { title: "Command", value: (p) => p.command, className: "command" },
+];
+
+const history = Object.fromEntries(CHART_METRICS.map((metric) => [metric.key, []]));
+let latest = null;
+let sortColumn = 3; // CPU %
+let sortAscending = false;
+
+const $ = (id) => document.getElementById(id);
+
+function formatSize(bytes) {
+    const gb = bytes / 1024 ** 3;
+    return gb >= 1 ? `${gb.toFixed(2)} GB` : `${(bytes / 1024 ** 2).toFixed(0)} MB`;
+}
+
+function formatRate(bytesPerSecond) {
+    const mb = bytesPerSecond / 1024 ** 2;
+    return mb >= 1 ? `${mb.toFixed(1)} MB/s` : `${(bytesPerSecond / 1024).toFixed(1)} KB/s`;
+}
+
+function element(tag, text, className) {
+    const node = document.createElement(tag);
+    if (text !== undefined) node.textContent = text;
+    if (className) node.className = className;
+    return node;
+}
+
+function bar(percent) {
+    const outer = element("div", undefined, "bar");
+    const inner = element("div");
+    inner.style.width = `${Math.min(100, Math.max(0, percent))}%`;
+    outer.append(inner);
+    return outer;
+}
+
+function renderCpu(cpu) {
+    $("cpu-brand").textContent = `${cpu.brand} (${cpu.vendor_id})`;
+    $("cpu-usage").textContent = `CPU Usage: ${cpu.usage_percent.toFixed(2)}%`;
+    $("cores").replaceChildren(
+        ...cpu.cores.map((core) => {
+            const row = element("div", undefined, "core");
+            row.append(
+                element("span", core.name),
+                bar(core.usage_percent),
+                element("span", `${Math.round(core.usage_percent)}%`),
+                element("span", `${core.frequency_mhz} MHz`),
+            );
+            return row;
+        }),
+    );
+}
+
+// Same split as the stacked bar in the desktop window
+function memorySegments(memory) {
+    const b = memory.breakdown;
+    if (!b) {
+        return [["Used", memory.used_bytes], ["Free", memory.free_bytes]];
+    }
+    const hugePages = b.huge_pages_total * b.huge_page_size_bytes;
+    const applications = Math.max(
+        0,
+        memory.total_bytes - memory.free_bytes - b.buffers_bytes - b.cached_bytes - b.slab_bytes - hugePages,
+    );
+    return [
+        ["Applications", applications],
+        ["Shared", b.shared_bytes],
+        ["Slab", b.slab_bytes],
+        ["Huge pages", hugePages],
+        ["Buffers", b.buffers_bytes],
+        ["Page cache", Math.max(0, b.cached_bytes - b.shared_bytes)],
+        ["Free", memory.free_bytes],
+    ];
+}
+
+function renderMemory(memory) {
+    const usedPercent = (memory.used_bytes / Math.max(1, memory.total_bytes)) * 100;
+    $("ram-info").textContent =
+        `RAM: ${formatSize(memory.used_bytes)} used of ${formatSize(memory.total_bytes)} (${usedPercent.toFixed(1)}%)`;
+
+    const segments = memorySegments(memory).filter(([, bytes]) => bytes > 0);
+    $("memory-bar").replaceChildren(
+        ...segments.map(([label, bytes]) => {
+            const part = element("div");
+            part.style.width = `${(bytes / Math.max(1, memory.total_bytes)) * 100}%`;
+            part.style.background = MEMORY_COLORS[label] ?? "transparent";
+            part.title = `${label}: ${formatSize(bytes)}`;
+            return part;
+        }),
+    );
+    $("memory-legend").replaceChildren(
+        ...segments.map(([label, bytes]) => {
+            const entry = element("span", `${label} ${formatSize(bytes)}`);
+            entry.style.setProperty("--swatch", MEMORY_COLORS[label] ?? "#80808030");
+            return entry;
+        }),
+    );
+
+    $("swap-info").textContent =
+        memory.swap_total_bytes > 0
+            ? `Swap: ${formatSize(memory.swap_used_bytes)} used of ${formatSize(memory.swap_total_bytes)} ` +
+              `(${((memory.swap_used_bytes / memory.swap_total_bytes) * 100).toFixed(1)}%)`
+            : "Swap: none";
+}
+
+function reading(value, unit) {
+    return value === null || value === undefined ? "N/A" : `${value}${unit}`;
+}
+
+function renderGpus(gpus) {
+    if (gpus.length === 0) {
+        $("gpu-info").textContent = "No GPU adapters found";
+        return;
+    }
+    $("gpu-info").textContent = gpus
+        .map((gpu) => {
+            const lines = [
+                `GPU: ${gpu.name} (${gpu.device_type})`,
+                `PCI ID: ${gpu.vendor_id.toString(16).padStart(4, "0")}:${gpu.device_id.toString(16).padStart(4, "0")}, ` +
+                    `Backends: ${gpu.backends.join(", ")}`,
+            ];
+            if (gpu.driver) lines.push(`Driver: ${gpu.driver} ${gpu.driver_version}`);
+            if (gpu.vram) {
+                const total = formatSize(gpu.vram.total_bytes);
+                lines.push(
+                    gpu.vram.used_bytes === null
+                        ? `VRAM: ${total} (${gpu.vram.source})`
+                        : `VRAM: ${formatSize(gpu.vram.used_bytes)} / ${total} used (${gpu.vram.source})`,
+                );
+            } else {
+                lines.push("VRAM: N/A");
+            }
+            const t = gpu.telemetry;
+            if (t) {
+                const watts = t.power_draw_milliwatts === null ? null : (t.power_draw_milliwatts / 1000).toFixed(1);
+                lines.push(
+                    `Utilization: ${reading(t.utilization_percent, "%")} (memory ${reading(t.memory_utilization_percent, "%")})`,
+                    `Clock Speed: ${reading(t.graphics_clock_mhz, " MHz")} graphics, ${reading(t.memory_clock_mhz, " MHz")} memory`,
+                    `Temperature: ${reading(t.temperature_celsius, " °C")}, Fan: ${reading(t.fan_speed_percent, "%")}, ` +
+                        `Power: ${reading(watts, " W")}`,
+                );
+            }
+            return lines.join("\n");
+        })
+        .join("\n\n");
+}
+
+function record(key, timestamp, value) {
+    const points = history[key];
+    if (value === null || value === undefined) return;
+    // Several collectors report per second; keep one point per snapshot timestamp
+    if (points.length > 0 && points[points.length - 1].timestamp >= timestamp) return;
+    points.push({ timestamp, value });
+    if (points.length > HISTORY_LEN) points.splice(0, points.length - HISTORY_LEN);
+}
+
+function recordSnapshot(snapshot) {
+    const timestamp = Date.parse(snapshot.timestamp);
+    const memory = snapshot.memory;
+    const gpuUtilization = snapshot.gpus
+        .map((gpu) => gpu.telemetry?.utilization_percent)
+        .find((value) => value !== null && value !== undefined);
+
+    record("cpu", timestamp, snapshot.cpu.usage_percent);
+    record("ram", timestamp, (memory.used_bytes / Math.max(1, memory.total_bytes)) * 100);
+    if (memory.swap_total_bytes > 0) {
+        record("swap", timestamp, (memory.swap_used_bytes / memory.swap_total_bytes) * 100);
+    }
+    record("gpu", timestamp, gpuUtilization);
+}
+
+function renderCharts() {
+    $("charts").replaceChildren(
+        ...CHART_METRICS.map(({ key, title }) => {
+            const points = history[key];
+            const chart = element("div", undefined, "chart");
+            const header = element("header");
+            const last = points[points.length - 1];
+            header.append(element("strong", title), element("span", last ? `${last.value.toFixed(1)}%` : "No data"));
+
+            const svg = document.createElementNS("http://www.w3.org/2000/svg", "svg");
+            svg.setAttribute("viewBox", "0 0 100 100");
+            svg.setAttribute("preserveAspectRatio", "none");
+            const line = document.createElementNS("http://www.w3.org/2000/svg", "polyline");
+            const step = 100 / Math.max(1, points.length - 1);
+            line.setAttribute(
+                "points",
+                points
+                    .map((point, index) => `${(index * step).toFixed(2)},${(100 - Math.min(100, Math.max(0, point.value))).toFixed(2)}`)
+                    .join(" "),
+            );
+            svg.append(line);
+
+            chart.append(header, svg);
+            return chart;
+        }),
+    );
+}
+
+function renderProcessHeader() {
+    $("process-header").replaceChildren(
+        ...PROCESS_COLUMNS.map((column, index) => {
+            const arrow = index === sortColumn ? (sortAscending ? " ▴" : " ▾") : "";
+            const th = element("th", column.title + arrow);
+            th.addEventListener("click", () => {
+                sortAscending = index === sortColumn ? !sortAscending : index <= 2;
+                sortColumn = index;
+                renderProcessHeader();
+                renderProcesses();
+            });
+            return th;
+        }),
+    );
+}
+
+function renderProcesses() {
+    if (!latest) return;
+    const filter = $("process-filter").value.trim().toLowerCase();
+    const column = PROCESS_COLUMNS[sortColumn];
+    const direction = sortAscending ? 1 : -1;
+
+    const rows = latest.processes
+        .filter(
+            (p) =>
+                !filter ||
+                [p.name, p.user ?? "", String(p.pid), p.command].some((field) => field.toLowerCase().includes(filter)),
+        )
+        .sort((a, b) => {
+            const left = column.value(a);
+            const right = column.value(b);
+            return (left < right ? -1 : left > right ? 1 : 0) * direction;
+        })
+        .slice(0, PROCESS_ROWS);
+
+    $("process-rows").replaceChildren(
+        ...rows.map((process) => {
+            const tr = element("tr");
+            tr.append(
+                ...PROCESS_COLUMNS.map((c) => element("td", String(c.text ? c.text(process) : c.value(process)), c.className)),
+            );
+            return tr;
+        }),
+    );
+}
+
+function render(snapshot) {
+    latest = snapshot;
+    renderCpu(snapshot.cpu);
+    renderMemory(snapshot.memory);
+    renderGpus(snapshot.gpus);
+    recordSnapshot(snapshot);
+    renderCharts();
+    renderProcesses();
+}
+
+async function loadHistory() {
+    await Promise.all(
+        CHART_METRICS.map(async ({ key }) => {
+            const response = await fetch(`/api/history?metric=${key}`);
+            if (!response.ok) return;
+            const body = await response.json();
+            history[key] = body.points.map((point) => ({ timestamp: Date.parse(point.timestamp), value: point.value }));
+        }),
+    );
+    renderCharts();
+}
+
+function setStatus(text, live) {
+    $("status").textContent = text;
+    $("status").classList.toggle("live", live);
+}
+
+function connect() {
+    const scheme = location.protocol === "https:" ? "wss" : "ws";
+    const socket = new WebSocket(`${scheme}://${location.host}/api/stream?metrics=cpu,memory,gpus,processes`);
+
+    socket.addEventListener("open", () => setStatus("Live", true));
+    socket.addEventListener("message", (event) => {
+        const frame = JSON.parse(event.data);
+        if (frame.snapshot) render(frame.snapshot);
+    });
+    socket.addEventListener("close", () => {
+        setStatus("Disconnected, retrying...", false);
+        setTimeout(connect, 2000);
+    });
+}
+
+$("process-filter").addEventListener("input", renderProcesses);
+renderProcessHeader();
+loadHistory().catch((err) => console.error("Could not load history", err)).finally(connect);